@@ -0,0 +1,232 @@
+use std::{f32::consts::TAU, str::FromStr};
+
+use crate::{path_opt::StyleErr, renderer::Canvas, Path, PathCode, Point};
+
+///
+/// Decoration at the start or end of an open path, such as an arrowhead.
+///
+/// The size is the length of the decoration in multiples of the line width.
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Arrow {
+    style: ArrowStyle,
+    size: f32,
+}
+
+impl Arrow {
+    pub const SIZE: f32 = 4.;
+
+    pub fn new(style: ArrowStyle, size: f32) -> Self {
+        assert!(size > 0., "arrow size must be positive {}", size);
+
+        Self {
+            style,
+            size,
+        }
+    }
+
+    #[inline]
+    pub fn style(&self) -> ArrowStyle {
+        self.style
+    }
+
+    #[inline]
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    ///
+    /// True if the decoration path is filled, false if it's stroked with the
+    /// path's line width.
+    ///
+    pub fn is_fill(&self) -> bool {
+        match self.style {
+            ArrowStyle::Triangle | ArrowStyle::Circle => true,
+            ArrowStyle::Simple | ArrowStyle::Open | ArrowStyle::Bar => false,
+        }
+    }
+
+    ///
+    /// Distance to shorten the path's stroke, so the stroke ends inside the
+    /// decoration instead of poking through its tip.
+    ///
+    pub fn shorten(&self, lw: f32) -> f32 {
+        let len = self.size * lw;
+
+        match self.style {
+            ArrowStyle::Simple => self.tip_inset(lw),
+            ArrowStyle::Triangle => len,
+            ArrowStyle::Open => len + self.tip_inset(lw),
+            ArrowStyle::Bar => 0.5 * lw,
+            ArrowStyle::Circle => 0.5 * len,
+        }
+    }
+
+    ///
+    /// Returns the decoration path with its outer edge at tip, where dir is
+    /// the unit direction of the path at the tip, pointing outward.
+    ///
+    pub fn path(&self, tip: Point, dir: Point, lw: f32) -> Path<Canvas> {
+        let len = self.size * lw;
+        let w = 0.5 * len;
+
+        // along the path direction and its normal
+        let at = |t: f32, n: f32| {
+            Point(
+                tip.x() + t * dir.x() - n * dir.y(),
+                tip.y() + t * dir.y() + n * dir.x()
+            )
+        };
+
+        let codes = match self.style {
+            ArrowStyle::Simple => {
+                // stroked tip extends past the vertex with a miter join
+                let t0 = - self.tip_inset(lw);
+
+                vec![
+                    PathCode::MoveTo(at(t0 - len, w)),
+                    PathCode::LineTo(at(t0, 0.)),
+                    PathCode::LineTo(at(t0 - len, -w)),
+                ]
+            }
+            ArrowStyle::Triangle => {
+                vec![
+                    PathCode::MoveTo(at(0., 0.)),
+                    PathCode::LineTo(at(-len, w)),
+                    PathCode::ClosePoly(at(-len, -w)),
+                ]
+            }
+            ArrowStyle::Open => {
+                let t0 = - self.tip_inset(lw);
+
+                vec![
+                    PathCode::MoveTo(at(t0, 0.)),
+                    PathCode::LineTo(at(t0 - len, w)),
+                    PathCode::ClosePoly(at(t0 - len, -w)),
+                ]
+            }
+            ArrowStyle::Bar => {
+                let t0 = -0.5 * lw;
+
+                vec![
+                    PathCode::MoveTo(at(t0, w)),
+                    PathCode::LineTo(at(t0, -w)),
+                ]
+            }
+            ArrowStyle::Circle => {
+                let r = 0.5 * len;
+                let n = 24;
+
+                let mut codes = Vec::<PathCode>::new();
+
+                for i in 0..n {
+                    let (sin, cos) = (TAU * i as f32 / n as f32).sin_cos();
+                    let p = at(r * cos - r, r * sin);
+
+                    codes.push(match i {
+                        0 => PathCode::MoveTo(p),
+                        i if i == n - 1 => PathCode::ClosePoly(p),
+                        _ => PathCode::LineTo(p),
+                    });
+                }
+
+                codes
+            }
+        };
+
+        Path::new(codes)
+    }
+
+    // distance of a stroked tip's miter beyond its vertex
+    fn tip_inset(&self, lw: f32) -> f32 {
+        // half-angle of the tip is atan(w / len) = atan(0.5)
+        let sin_half = 0.5f32.atan().sin();
+
+        0.5 * lw / sin_half
+    }
+}
+
+impl From<ArrowStyle> for Arrow {
+    fn from(style: ArrowStyle) -> Self {
+        Arrow::new(style, Arrow::SIZE)
+    }
+}
+
+impl From<&str> for Arrow {
+    fn from(name: &str) -> Self {
+        match ArrowStyle::from_str(name) {
+            Ok(style) => style.into(),
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArrowStyle {
+    /// Open chevron drawn with two strokes
+    Simple,
+    /// Filled triangle
+    Triangle,
+    /// Triangle outline
+    Open,
+    /// Bar perpendicular to the line
+    Bar,
+    /// Filled circle
+    Circle,
+}
+
+impl FromStr for ArrowStyle {
+    type Err = StyleErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "simple" => Ok(Self::Simple),
+            ">" => Ok(Self::Simple),
+            "triangle" => Ok(Self::Triangle),
+            "|>" => Ok(Self::Triangle),
+            "open" => Ok(Self::Open),
+            "bar" => Ok(Self::Bar),
+            "|" => Ok(Self::Bar),
+            "circle" => Ok(Self::Circle),
+            "o" => Ok(Self::Circle),
+            _ => Err(StyleErr(format!("'{}' is an unknown arrow style", name)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Path, PathCode, Point, renderer::Canvas};
+
+    use super::{Arrow, ArrowStyle};
+
+    #[test]
+    fn triangle_tip() {
+        let arrow = Arrow::new(ArrowStyle::Triangle, 4.);
+
+        let path = arrow.path(Point(10., 0.), Point(1., 0.), 2.);
+
+        assert_eq!(path.codes()[0], PathCode::MoveTo(Point(10., 0.)));
+        assert_eq!(path.codes()[1], PathCode::LineTo(Point(2., 4.)));
+        assert_eq!(path.codes()[2], PathCode::ClosePoly(Point(2., -4.)));
+
+        assert_eq!(arrow.shorten(2.), 8.);
+    }
+
+    #[test]
+    fn shorten_path() {
+        let path = Path::<Canvas>::from([[0., 0.], [10., 0.]]);
+
+        let arrow = Arrow::new(ArrowStyle::Triangle, 2.);
+        let path = path.shorten(arrow.shorten(1.), arrow.shorten(2.));
+
+        assert_eq!(path.codes()[0], PathCode::MoveTo(Point(2., 0.)));
+        assert_eq!(path.codes()[1], PathCode::LineTo(Point(6., 0.)));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Arrow::from("|>"), Arrow::new(ArrowStyle::Triangle, Arrow::SIZE));
+        assert!("arrow".parse::<ArrowStyle>().is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use core::fmt;
 use std::str::FromStr;
 
-use crate::color_data::lookup_color_name;
+use crate::{color_data::lookup_color_name, ColorDeficiency};
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct Color(pub u32);
@@ -291,6 +291,38 @@ impl Color {
             arg
         )
     }
+
+    ///
+    /// Color as seen with a color vision deficiency.
+    ///
+    pub fn simulate(&self, deficiency: ColorDeficiency) -> Color {
+        deficiency.simulate(*self)
+    }
+
+    /// WCAG relative luminance
+    #[inline]
+    pub fn luminance(&self) -> f32 {
+        let [r, g, b, _] = self.to_lrgb();
+
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    ///
+    /// WCAG contrast ratio from 1 to 21. Text needs at least 4.5 for AA.
+    ///
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let l0 = self.luminance();
+        let l1 = other.luminance();
+
+        (l0.max(l1) + 0.05) / (l0.min(l1) + 0.05)
+    }
+
+    ///
+    /// CIEDE2000 color difference, where about 2.3 is just noticeable.
+    ///
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        ciede2000(self.to_lab(), other.to_lab())
+    }
 }
 
 // msh in Morland, Diverging Color Maps for Scientific Visualization
@@ -304,6 +336,77 @@ fn lab_to_msh(l: f32, a: f32, b: f32) -> [f32; 3] {
     ]
 }
 
+// Sharma, Wu and Dalal, The CIEDE2000 Color-Difference Formula
+fn ciede2000(lab0: [f32; 3], lab1: [f32; 3]) -> f32 {
+    let [l0, a0, b0] = lab0;
+    let [l1, a1, b1] = lab1;
+
+    let c_bar = 0.5 * ((a0 * a0 + b0 * b0).sqrt() + (a1 * a1 + b1 * b1).sqrt());
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1. - (c_bar7 / (c_bar7 + 25f32.powi(7))).sqrt());
+
+    let a0 = (1. + g) * a0;
+    let a1 = (1. + g) * a1;
+
+    let c0 = (a0 * a0 + b0 * b0).sqrt();
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+
+    let hue = |b: f32, a: f32| {
+        if a == 0. && b == 0. { 0. } else { b.atan2(a).to_degrees().rem_euclid(360.) }
+    };
+
+    let h0 = hue(b0, a0);
+    let h1 = hue(b1, a1);
+
+    let dl = l1 - l0;
+    let dc = c1 - c0;
+
+    let dh = if c0 * c1 == 0. {
+        0.
+    } else if (h1 - h0).abs() <= 180. {
+        h1 - h0
+    } else if h1 - h0 > 180. {
+        h1 - h0 - 360.
+    } else {
+        h1 - h0 + 360.
+    };
+    let dh = 2. * (c0 * c1).sqrt() * (0.5 * dh).to_radians().sin();
+
+    let l_bar = 0.5 * (l0 + l1);
+    let c_bar = 0.5 * (c0 + c1);
+
+    let h_bar = if c0 * c1 == 0. {
+        h0 + h1
+    } else if (h0 - h1).abs() <= 180. {
+        0.5 * (h0 + h1)
+    } else if h0 + h1 < 360. {
+        0.5 * (h0 + h1 + 360.)
+    } else {
+        0.5 * (h0 + h1 - 360.)
+    };
+
+    let t = 1.
+        - 0.17 * (h_bar - 30.).to_radians().cos()
+        + 0.24 * (2. * h_bar).to_radians().cos()
+        + 0.32 * (3. * h_bar + 6.).to_radians().cos()
+        - 0.20 * (4. * h_bar - 63.).to_radians().cos();
+
+    let d_theta = 30. * (- ((h_bar - 275.) / 25.).powi(2)).exp();
+    let c_bar7 = c_bar.powi(7);
+    let r_c = 2. * (c_bar7 / (c_bar7 + 25f32.powi(7))).sqrt();
+    let l50 = (l_bar - 50.).powi(2);
+    let s_l = 1. + 0.015 * l50 / (20. + l50).sqrt();
+    let s_c = 1. + 0.045 * c_bar;
+    let s_h = 1. + 0.015 * c_bar * t;
+    let r_t = - (2. * d_theta).to_radians().sin() * r_c;
+
+    let dl = dl / s_l;
+    let dc = dc / s_c;
+    let dh = dh / s_h;
+
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt()
+}
+
 fn msh_to_lab(m: f32, s: f32, h: f32) -> [f32; 3] {
     [
         m * s.cos(),
@@ -342,51 +445,212 @@ impl From<(f32, f32, f32, f32)> for Color {
     }
 }
 
-impl From<&str> for Color {
-    fn from(name: &str) -> Self {
-        name.parse::<Color>().unwrap()
+///
+/// Converts a color spec, as `str::parse`, returning a ColorErr when it's
+/// invalid.
+///
+impl TryFrom<&str> for Color {
+    type Error = ColorErr;
 
-        /*
-        if let Some(color) = super::color_data::lookup_color(name) {
-            return color;
-        }
-
-        panic!("'{}' is an unknown color name", name);
-        // TODO: parse '#', 'rgb(...)'
-
-        //return Color::black();
-        */
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Self::from_str(name)
     }
 }
 
 impl FromStr for Color {
     type Err = ColorErr;
 
+    ///
+    /// Parses a color name, "#rgb", "#rgba", "#rrggbb", "#rrggbbaa",
+    /// "rgb()", "rgba()", "hsl()", "hsla()", a "C0" cycle color, or a "0.5"
+    /// grey value. Names ignore case, as in "Red".
+    ///
     fn from_str(name: &str) -> Result<Self, Self::Err> {
-        if let Some(color) = super::color_data::lookup_color(name) {
+        let spec = name.trim();
+
+        if let Some(color) = super::color_data::lookup_color(&spec.to_ascii_lowercase()) {
             return Ok(color);
         }
 
-        if name.starts_with("#") {
-            let mut value = 0;
-            for ch in name.as_bytes().iter().skip(1) {
-                match ch {
-                    b'0'..=b'9' => { value = 16 * value + *ch as u32 - b'0' as u32; }
-                    b'a'..=b'f' => { value = 16 * value + *ch as u32 - b'a' as u32 + 10; }
-                    b'A'..=b'F' => { value = 16 * value + *ch as u32 - b'A' as u32 + 10; }
-                    _ => {
-                        return Err(ColorErr(format!("Invalid rgb color spec {:?}", name)));
-                    }
+        if let Some(hex) = spec.strip_prefix("#") {
+            return parse_hex(hex)
+                .ok_or_else(|| ColorErr(format!("Invalid rgb color spec {:?}", name)));
+        }
+
+        if let Some(open) = spec.find('(') {
+            let func = spec[..open].trim().to_ascii_lowercase();
+
+            let args = match spec[open + 1..].strip_suffix(')') {
+                Some(args) => args,
+                None => {
+                    return Err(ColorErr(format!("Unclosed color function {:?}", name)));
                 }
+            };
+
+            let color = match func.as_str() {
+                "rgb" | "rgba" => parse_rgb(args),
+                "hsl" | "hsla" => parse_hsl(args),
+                _ => {
+                    return Err(ColorErr(format!("'{}' is an unknown color function", func)));
+                }
+            };
+
+            return color.ok_or_else(|| ColorErr(format!("Invalid color spec {:?}", name)));
+        }
+
+        if let Some(index) = spec.strip_prefix("C") {
+            if let Ok(index) = index.parse::<usize>() {
+                return Ok(super::color_data::cycle_color(index));
             }
+        }
 
-            return Ok(Color::from(value))
+        if let Ok(grey) = spec.parse::<f32>() {
+            if 0. <= grey && grey <= 1. {
+                return Ok(Color::from_grey(grey));
+            } else {
+                return Err(ColorErr(format!("Grey value must be in [0, 1] {:?}", name)));
+            }
         }
 
-        return Err(ColorErr(format!("'{}' is an unknown color name", name)));
+        Err(ColorErr(format!("'{}' is an unknown color name", name)))
     }
 }
 
+fn parse_hex(hex: &str) -> Option<Color> {
+    let mut digits = Vec::<u32>::new();
+
+    for ch in hex.chars() {
+        digits.push(ch.to_digit(16)?);
+    }
+
+    let rgba = match digits.len() {
+        3 | 4 => {
+            // short forms repeat each digit, so "#abc" is "#aabbcc"
+            let mut value = 0;
+
+            for digit in &digits {
+                value = 256 * value + 17 * digit;
+            }
+
+            if digits.len() == 3 { 256 * value + 0xff } else { value }
+        }
+        6 | 8 => {
+            let mut value = 0;
+
+            for digit in &digits {
+                value = 16 * value + digit;
+            }
+
+            if digits.len() == 6 { 256 * value + 0xff } else { value }
+        }
+        _ => return None,
+    };
+
+    Some(Color(rgba))
+}
+
+// "r, g, b[, a]" or "r g b[ / a]" with 0-255 or percentage components
+fn parse_rgb(args: &str) -> Option<Color> {
+    let args = split_args(args)?;
+
+    let r = parse_component(args[0], 255.)?;
+    let g = parse_component(args[1], 255.)?;
+    let b = parse_component(args[2], 255.)?;
+    let a = parse_alpha(args.get(3))?;
+
+    Some(round_rgba(r, g, b, a))
+}
+
+// "h, s%, l%[, a]" where the hue is in degrees
+fn parse_hsl(args: &str) -> Option<Color> {
+    let args = split_args(args)?;
+
+    let h = args[0].strip_suffix("deg").unwrap_or(args[0]).trim().parse::<f32>().ok()?;
+    let s = parse_percent(args[1])?;
+    let l = parse_percent(args[2])?;
+    let a = parse_alpha(args.get(3))?;
+
+    let [r, g, b] = hsl_to_rgb(h.rem_euclid(360.) / 360., s, l);
+
+    Some(round_rgba(r, g, b, a))
+}
+
+// unlike from_rgba, rounds so "rgb(128, 0, 0)" is exactly 0x800000
+fn round_rgba(r: f32, g: f32, b: f32, a: f32) -> Color {
+    let r = (r * 255.).round() as u32;
+    let g = (g * 255.).round() as u32;
+    let b = (b * 255.).round() as u32;
+    let a = (a * 255.).round() as u32;
+
+    Color((r << 24) | (g << 16) | (b << 8) | a)
+}
+
+fn split_args(args: &str) -> Option<Vec<&str>> {
+    let args: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|arg| ! arg.is_empty())
+        .collect();
+
+    if args.len() == 3 || args.len() == 4 {
+        Some(args)
+    } else {
+        None
+    }
+}
+
+// component scaled to [0, 1], where max is the value for a non-percentage
+fn parse_component(arg: &str, max: f32) -> Option<f32> {
+    let value = match arg.strip_suffix("%") {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.,
+        None => arg.parse::<f32>().ok()? / max,
+    };
+
+    if value.is_finite() {
+        Some(value.clamp(0., 1.))
+    } else {
+        None
+    }
+}
+
+fn parse_percent(arg: &str) -> Option<f32> {
+    match arg.strip_suffix("%") {
+        Some(_) => parse_component(arg, 100.),
+        None => None,
+    }
+}
+
+fn parse_alpha(arg: Option<&&str>) -> Option<f32> {
+    match arg {
+        Some(arg) => parse_component(arg, 1.),
+        None => Some(1.),
+    }
+}
+
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [f32; 3] {
+    let q = if l < 0.5 { l * (1. + s) } else { l + s - l * s };
+    let p = 2. * l - q;
+
+    fn hue(p: f32, q: f32, t: f32) -> f32 {
+        let t = t.rem_euclid(1.);
+
+        if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        }
+    }
+
+    [
+        hue(p, q, h + 1. / 3.),
+        hue(p, q, h),
+        hue(p, q, h - 1. / 3.),
+    ]
+}
+
 #[derive(Clone)]
 pub struct Colors {
     pub colors: Vec<Color>,
@@ -404,15 +668,17 @@ impl Colors {
     }
 }
 
-impl<const N: usize> From<[&str; N]> for Colors {
-    fn from(value: [&str; N]) -> Self {
+impl<const N: usize> TryFrom<[&str; N]> for Colors {
+    type Error = ColorErr;
+
+    fn try_from(value: [&str; N]) -> Result<Self, Self::Error> {
         let mut vec = Vec::new();
 
         for name in value {
-            vec.push(Color::from(name));
+            vec.push(Color::from_str(name)?);
         }
 
-        Colors::new(vec)
+        Ok(Colors::new(vec))
     }
 }
 
@@ -421,7 +687,7 @@ pub struct ColorErr(pub String);
 
 #[cfg(test)]
 mod test {
-    use crate::Color;
+    use crate::{Color, Colors};
 
     /// CIE xyz color
     #[test]
@@ -558,4 +824,85 @@ mod test {
         let [m, s, h] = Color(0xffff_fcff).to_msh();
         assert_eq!(Color::from_msh(m, s, h), Color(0xffff_fbff));
     }
+
+    #[test]
+    fn parse() {
+        assert_eq!("#abc".parse::<Color>().unwrap(), Color(0xaabb_ccff));
+        assert_eq!("#abcd".parse::<Color>().unwrap(), Color(0xaabb_ccdd));
+        assert_eq!("#102030".parse::<Color>().unwrap(), Color(0x1020_30ff));
+        assert_eq!("#10203040".parse::<Color>().unwrap(), Color(0x1020_3040));
+
+        assert_eq!("rgb(128, 0, 255)".parse::<Color>().unwrap(), Color(0x8000_ffff));
+        assert_eq!("rgba(100%, 0%, 0%, 0.5)".parse::<Color>().unwrap(), Color(0xff00_0080));
+        assert_eq!("rgb(0 0 255 / 50%)".parse::<Color>().unwrap(), Color(0x0000_ff80));
+        assert_eq!("hsl(120, 100%, 50%)".parse::<Color>().unwrap(), Color(0x00ff_00ff));
+        assert_eq!("hsla(240deg, 100%, 50%, 0)".parse::<Color>().unwrap(), Color(0x0000_ff00));
+
+        assert_eq!("none".parse::<Color>().unwrap(), Color::none());
+        assert_eq!("Red".parse::<Color>().unwrap(), "red".parse::<Color>().unwrap());
+        assert_eq!(" DarkBlue ".parse::<Color>().unwrap(), "darkblue".parse::<Color>().unwrap());
+        assert_eq!("C0".parse::<Color>().unwrap(), Color(0x1f77_b4ff));
+        assert_eq!("C11".parse::<Color>().unwrap(), Color(0xff7f_0eff));
+        assert_eq!("0.5".parse::<Color>().unwrap(), Color::from_grey(0.5));
+
+        assert!("#abcde".parse::<Color>().is_err());
+        assert!("rgb(1, 2)".parse::<Color>().is_err());
+        assert!("cmyk(1, 2, 3)".parse::<Color>().is_err());
+        assert!("1.5".parse::<Color>().is_err());
+        assert!("not-a-color".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn try_from_str() {
+        assert_eq!(Color::try_from("Red").unwrap(), "red".parse::<Color>().unwrap());
+        assert!(Color::try_from("not-a-color").is_err());
+
+        assert!(Colors::try_from(["red", "#0000ff"]).is_ok());
+        assert!(Colors::try_from(["red", "not-a-color"]).is_err());
+    }
+
+    #[test]
+    fn contrast() {
+        assert!((Color::black().contrast_ratio(&Color::white()) - 21.).abs() < 1e-3);
+        assert!((Color::white().contrast_ratio(&Color::black()) - 21.).abs() < 1e-3);
+        assert_eq!(Color::white().contrast_ratio(&Color::white()), 1.);
+
+        assert_eq!(Color::white().delta_e(&Color::white()), 0.);
+
+        let red = Color(0xff00_00ff);
+        let near_red = Color(0xfe01_00ff);
+        assert!(red.delta_e(&near_red) < 1.);
+        assert!(red.delta_e(&Color(0x00ff_00ff)) > 50.);
+    }
+
+    #[test]
+    fn ciede2000() {
+        // Sharma, Wu and Dalal, The CIEDE2000 Color-Difference Formula, table 1
+        let pairs = [
+            ([50., 2.6772, -79.7751], [50., 0., -82.7485], 2.0425),
+            ([50., 3.1571, -77.2803], [50., 0., -82.7485], 2.8615),
+            ([50., -1.3802, -84.2814], [50., 0., -82.7485], 1.),
+            // achromatic, where the hue is 0
+            ([50., 0., 0.], [50., -1., 2.], 2.3669),
+            // hues just under and just over 180 degrees apart, where the
+            // hue difference and mean hue wrap around 360
+            ([50., 2.49, -0.001], [50., -2.49, 0.0009], 7.1792),
+            ([50., 2.49, -0.001], [50., -2.49, 0.0011], 7.2195),
+            ([50., -0.001, 2.49], [50., 0.0009, -2.49], 4.8045),
+            ([50., -0.001, 2.49], [50., 0.0011, -2.49], 4.7461),
+            ([50., 2.5, 0.], [50., 0., -2.5], 4.3065),
+            ([50., 2.5, 0.], [73., 25., -18.], 27.1492),
+            ([60.2574, -34.0099, 36.2677], [60.4626, -34.1751, 39.4387], 1.2644),
+            ([63.0109, -31.0961, -5.8663], [62.8187, -29.7946, -4.0864], 1.2630),
+            ([22.7233, 20.0904, -46.6940], [23.0331, 14.9730, -42.5619], 2.0373),
+            ([2.0776, 0.0795, -1.1350], [0.9033, -0.0636, -0.5514], 0.9082),
+        ];
+
+        for (lab0, lab1, expect) in pairs {
+            // both orders, so each hue branch is taken from either side
+            for delta in [super::ciede2000(lab0, lab1), super::ciede2000(lab1, lab0)] {
+                assert!((delta - expect).abs() < 1e-3, "{:?} {:?} {} {}", lab0, lab1, delta, expect);
+            }
+        }
+    }
 }
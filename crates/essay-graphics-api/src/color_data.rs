@@ -8,6 +8,15 @@ pub(crate) fn lookup_color(name: &str) -> Option<Color> {
     COLORMAP.lock().unwrap().color(name)
 }
 
+///
+/// Matplotlib-style "C0".."C9" cycle colors, wrapping after the tenth.
+///
+pub(crate) fn cycle_color(index: usize) -> Color {
+    let data = tableau_color_data();
+
+    Color::from(data[index % data.len()].1)
+}
+
 pub(crate) fn lookup_color_name(color: &Color) -> String {
     COLORMAP.lock().unwrap().best_name(color)
 }
@@ -104,7 +113,7 @@ fn digit_color_data() -> Vec<(&'static str, u32)> {
 ///
 /// Tableau colors
 ///
-fn _tableau_color_data() -> Vec<(&'static str, u32)> {
+fn tableau_color_data() -> Vec<(&'static str, u32)> {
     return vec![
         ("blue", 0x1f77b4),
         ("orange", 0xff7f0e),
@@ -0,0 +1,154 @@
+use std::str::FromStr;
+
+use crate::{color::ColorErr, Color};
+
+///
+/// Dichromatic color vision deficiency, for previewing how a figure
+/// appears to color blind readers.
+///
+/// Simulation uses the full-severity matrices in linear RGB from Machado,
+/// Oliveira and Fernandes, A Physiologically-based Model for Simulation of
+/// Color Vision Deficiency.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorDeficiency {
+    /// Missing long-wavelength (red) cones
+    Protanopia,
+    /// Missing medium-wavelength (green) cones
+    Deuteranopia,
+    /// Missing short-wavelength (blue) cones
+    Tritanopia,
+}
+
+impl ColorDeficiency {
+    fn matrix(&self) -> &'static [[f32; 3]; 3] {
+        match self {
+            ColorDeficiency::Protanopia => &PROTANOPIA,
+            ColorDeficiency::Deuteranopia => &DEUTERANOPIA,
+            ColorDeficiency::Tritanopia => &TRITANOPIA,
+        }
+    }
+
+    ///
+    /// Simulated color, keeping the alpha.
+    ///
+    pub fn simulate(&self, color: Color) -> Color {
+        let [r, g, b, _] = color.to_lrgb();
+
+        let [r, g, b] = self.transform([r, g, b]);
+
+        Color::from_rgba(
+            Color::lrgb_to_srgb(r),
+            Color::lrgb_to_srgb(g),
+            Color::lrgb_to_srgb(b),
+            color.alpha(),
+        )
+    }
+
+    fn transform(&self, lrgb: [f32; 3]) -> [f32; 3] {
+        let m = self.matrix();
+
+        [
+            (m[0][0] * lrgb[0] + m[0][1] * lrgb[1] + m[0][2] * lrgb[2]).clamp(0., 1.),
+            (m[1][0] * lrgb[0] + m[1][1] * lrgb[1] + m[1][2] * lrgb[2]).clamp(0., 1.),
+            (m[2][0] * lrgb[0] + m[2][1] * lrgb[1] + m[2][2] * lrgb[2]).clamp(0., 1.),
+        ]
+    }
+
+    ///
+    /// Simulates the deficiency in place on an sRGB RGBA buffer, such as a
+    /// rendered hardcopy. Alpha is unchanged.
+    ///
+    pub fn apply_rgba(&self, data: &mut [u8]) {
+        assert!(data.len() % 4 == 0, "rgba buffer length must be a multiple of 4");
+
+        // sRGB to linear for each 8-bit value
+        let to_lrgb: Vec<f32> = (0..256)
+            .map(|v| Color::srgb_to_lrgb(v as f32 / 255.))
+            .collect();
+
+        for pixel in data.chunks_exact_mut(4) {
+            let lrgb = [
+                to_lrgb[pixel[0] as usize],
+                to_lrgb[pixel[1] as usize],
+                to_lrgb[pixel[2] as usize],
+            ];
+
+            let [r, g, b] = self.transform(lrgb);
+
+            pixel[0] = (Color::lrgb_to_srgb(r) * 255.).round() as u8;
+            pixel[1] = (Color::lrgb_to_srgb(g) * 255.).round() as u8;
+            pixel[2] = (Color::lrgb_to_srgb(b) * 255.).round() as u8;
+        }
+    }
+}
+
+impl FromStr for ColorDeficiency {
+    type Err = ColorErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "protanopia" => Ok(Self::Protanopia),
+            "deuteranopia" => Ok(Self::Deuteranopia),
+            "tritanopia" => Ok(Self::Tritanopia),
+            _ => Err(ColorErr(format!("'{}' is an unknown color deficiency", name)))
+        }
+    }
+}
+
+impl From<&str> for ColorDeficiency {
+    fn from(name: &str) -> Self {
+        match Self::from_str(name) {
+            Ok(deficiency) => deficiency,
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+}
+
+const PROTANOPIA: [[f32; 3]; 3] = [
+    [0.152286, 1.052583, -0.204868],
+    [0.114503, 0.786281, 0.099216],
+    [-0.003882, -0.048116, 1.051998],
+];
+
+const DEUTERANOPIA: [[f32; 3]; 3] = [
+    [0.367322, 0.860646, -0.227968],
+    [0.280085, 0.672501, 0.047413],
+    [-0.011820, 0.042940, 0.968881],
+];
+
+const TRITANOPIA: [[f32; 3]; 3] = [
+    [1.255528, -0.076749, -0.178779],
+    [-0.078411, 0.930809, 0.147602],
+    [0.004733, 0.691367, 0.303900],
+];
+
+#[cfg(test)]
+mod test {
+    use crate::Color;
+
+    use super::ColorDeficiency;
+
+    #[test]
+    fn simulate() {
+        // greys are unchanged since each matrix row sums to one
+        let grey = Color(0x8080_80ff);
+
+        for deficiency in ["protanopia", "deuteranopia", "tritanopia"] {
+            let color = ColorDeficiency::from(deficiency).simulate(grey);
+
+            assert!((color.red() - grey.red()).abs() <= 1. / 255.);
+            assert!((color.blue() - grey.blue()).abs() <= 1. / 255.);
+        }
+
+        // red and green become hard to tell apart
+        let red = ColorDeficiency::Deuteranopia.simulate(Color(0xd627_28ff));
+        let green = ColorDeficiency::Deuteranopia.simulate(Color(0x2ca0_2cff));
+
+        assert!(red.delta_e(&green) < Color(0xd627_28ff).delta_e(&Color(0x2ca0_2cff)));
+
+        let mut data = [0xd6, 0x27, 0x28, 0x80];
+        ColorDeficiency::Deuteranopia.apply_rgba(&mut data);
+        assert_eq!(data[3], 0x80);
+    }
+}
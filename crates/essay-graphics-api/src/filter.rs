@@ -0,0 +1,281 @@
+use crate::{Color, Point};
+
+///
+/// Post-processing filter for an offscreen group or a path.
+///
+/// Blur radii are the Gaussian's standard deviation in logical pixels, like
+/// the CSS blur() filter, and are scaled by the renderer's scale_factor.
+/// A negative or non-finite radius or offset, which the variants allow
+/// unlike the constructors, is treated as 0.
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Filter {
+    /// Gaussian blur
+    Blur(f32),
+    /// Blurred copy of the source's alpha, offset and filled with a color,
+    /// drawn under the source. The offset is in canvas coordinates, so a
+    /// negative y offset moves the shadow down.
+    DropShadow {
+        offset: Point,
+        radius: f32,
+        color: Color,
+    },
+}
+
+impl Filter {
+    pub fn blur(radius: f32) -> Self {
+        assert!(radius >= 0., "blur radius must not be negative {}", radius);
+
+        Filter::Blur(radius)
+    }
+
+    pub fn drop_shadow(
+        offset: impl Into<Point>,
+        radius: f32,
+        color: impl Into<Color>
+    ) -> Self {
+        assert!(radius >= 0., "shadow radius must not be negative {}", radius);
+
+        Filter::DropShadow {
+            offset: offset.into(),
+            radius,
+            color: color.into(),
+        }
+    }
+
+    ///
+    /// Blur standard deviation in physical pixels.
+    ///
+    pub fn sigma(&self, scale_factor: f32) -> f32 {
+        let radius = match self {
+            Filter::Blur(radius) => *radius,
+            Filter::DropShadow { radius, .. } => *radius,
+        };
+
+        if radius.is_finite() && radius > 0. {
+            radius * scale_factor
+        } else {
+            0.
+        }
+    }
+
+    ///
+    /// Shadow offset in physical pixels, with rows from top to bottom as in
+    /// a render target. A blur has no offset.
+    ///
+    pub fn offset(&self, scale_factor: f32) -> Point {
+        match self {
+            Filter::Blur(_) => Point(0., 0.),
+            Filter::DropShadow { offset, .. } => {
+                let finite = |v: f32| if v.is_finite() { v } else { 0. };
+
+                Point(finite(offset.x()) * scale_factor, - finite(offset.y()) * scale_factor)
+            }
+        }
+    }
+
+    ///
+    /// Applies the filter in place to an sRGB RGBA buffer with rows from top
+    /// to bottom, such as a buffer read from a hardcopy. The result matches
+    /// the GPU group filter for the same scale_factor.
+    ///
+    pub fn apply_rgba(&self, data: &mut [u8], width: usize, height: usize, scale_factor: f32) {
+        assert_eq!(data.len(), 4 * width * height, "rgba buffer must be width * height * 4");
+
+        let sigma = self.sigma(scale_factor);
+
+        // blurs are in premultiplied linear rgb, like the GPU filters
+        let src: Vec<[f32; 4]> = data.chunks_exact(4).map(|p| {
+            let color = Color::from((p[0] as u32, p[1] as u32, p[2] as u32))
+                .set_alpha(p[3] as f32 / 255.);
+            let [r, g, b, a] = color.to_lrgb();
+
+            [r * a, g * a, b * a, a]
+        }).collect();
+
+        let out = match self {
+            Filter::Blur(_) => blur(&src, width, height, sigma),
+            Filter::DropShadow { color, .. } => {
+                let offset = self.offset(scale_factor);
+
+                let dx = offset.x().round() as isize;
+                let dy = offset.y().round() as isize;
+
+                let mut shadow = vec![[0.; 4]; src.len()];
+
+                for y in 0..height as isize {
+                    for x in 0..width as isize {
+                        let (sx, sy) = (x - dx, y - dy);
+
+                        if 0 <= sx && sx < width as isize && 0 <= sy && sy < height as isize {
+                            let a = src[sy as usize * width + sx as usize][3];
+                            shadow[y as usize * width + x as usize] = [a; 4];
+                        }
+                    }
+                }
+
+                let shadow = blur(&shadow, width, height, sigma);
+                let [r, g, b, a] = color.to_lrgb();
+
+                src.iter().zip(shadow.iter()).map(|(s, d)| {
+                    // source over the tinted shadow
+                    let t = a * d[3] * (1. - s[3]);
+
+                    [s[0] + r * t, s[1] + g * t, s[2] + b * t, s[3] + t]
+                }).collect()
+            }
+        };
+
+        for (p, c) in data.chunks_exact_mut(4).zip(out.iter()) {
+            let a = c[3].clamp(0., 1.);
+
+            let (r, g, b) = if a > 0. {
+                (c[0] / a, c[1] / a, c[2] / a)
+            } else {
+                (0., 0., 0.)
+            };
+
+            p[0] = to_u8(Color::lrgb_to_srgb(r));
+            p[1] = to_u8(Color::lrgb_to_srgb(g));
+            p[2] = to_u8(Color::lrgb_to_srgb(b));
+            p[3] = to_u8(a);
+        }
+    }
+}
+
+///
+/// Normalized Gaussian weights from -n to n, where n is 3 sigma.
+///
+fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    let n = (3. * sigma).ceil() as isize;
+
+    if n == 0 {
+        return vec![1.];
+    }
+
+    let weights: Vec<f32> = (-n..=n).map(|k| {
+        (-0.5 * (k * k) as f32 / (sigma * sigma)).exp()
+    }).collect();
+
+    let sum: f32 = weights.iter().sum();
+
+    weights.iter().map(|w| w / sum).collect()
+}
+
+// separable blur, where pixels outside the image are transparent
+fn blur(src: &[[f32; 4]], width: usize, height: usize, sigma: f32) -> Vec<[f32; 4]> {
+    let kernel = gaussian_kernel(sigma);
+
+    let tmp = blur_pass(src, width, height, &kernel, 1, 0);
+
+    blur_pass(&tmp, width, height, &kernel, 0, 1)
+}
+
+fn blur_pass(
+    src: &[[f32; 4]],
+    width: usize,
+    height: usize,
+    kernel: &[f32],
+    dx: isize,
+    dy: isize,
+) -> Vec<[f32; 4]> {
+    let n = (kernel.len() / 2) as isize;
+    let mut out = vec![[0.; 4]; src.len()];
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let mut sum = [0.; 4];
+
+            for (i, w) in kernel.iter().enumerate() {
+                let k = i as isize - n;
+                let (sx, sy) = (x + k * dx, y + k * dy);
+
+                if 0 <= sx && sx < width as isize && 0 <= sy && sy < height as isize {
+                    let p = src[sy as usize * width + sx as usize];
+
+                    for c in 0..4 {
+                        sum[c] += w * p[c];
+                    }
+                }
+            }
+
+            out[y as usize * width + x as usize] = sum;
+        }
+    }
+
+    out
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, Point};
+
+    use super::{gaussian_kernel, Filter};
+
+    #[test]
+    fn kernel() {
+        let kernel = gaussian_kernel(2.);
+
+        assert_eq!(kernel.len(), 13);
+        assert!((kernel.iter().sum::<f32>() - 1.).abs() < 1e-5);
+        assert_eq!(kernel[0], kernel[12]);
+
+        assert_eq!(gaussian_kernel(0.), vec![1.]);
+    }
+
+    #[test]
+    fn invalid_variants() {
+        assert_eq!(Filter::Blur(-1.).sigma(2.), 0.);
+        assert_eq!(Filter::Blur(f32::NAN).sigma(2.), 0.);
+        assert_eq!(Filter::Blur(f32::INFINITY).sigma(2.), 0.);
+        assert_eq!(Filter::Blur(1.5).sigma(2.), 3.);
+
+        let shadow = Filter::DropShadow {
+            offset: Point(f32::NAN, 2.),
+            radius: f32::NAN,
+            color: Color(0x000000ff),
+        };
+
+        assert_eq!(shadow.sigma(1.), 0.);
+        assert_eq!(shadow.offset(1.), Point(0., -2.));
+
+        // the cpu path goes through sigma, so invalid radii don't blur
+        let mut data = vec![255u8; 4 * 3 * 3];
+        Filter::Blur(f32::NAN).apply_rgba(&mut data, 3, 3, 1.);
+        assert!(data.iter().all(|v| *v == 255));
+    }
+
+    #[test]
+    fn blur_spreads_alpha() {
+        let mut data = vec![0u8; 4 * 5 * 5];
+        data[4 * 12..4 * 13].copy_from_slice(&[255, 255, 255, 255]);
+
+        Filter::blur(1.).apply_rgba(&mut data, 5, 5, 1.);
+
+        let alpha = |x: usize, y: usize| data[4 * (y * 5 + x) + 3];
+
+        assert!(alpha(2, 2) < 255);
+        assert!(alpha(1, 2) > 0);
+        assert_eq!(alpha(1, 2), alpha(3, 2));
+        assert_eq!(alpha(2, 1), alpha(2, 3));
+        // color is unchanged where alpha is non-zero
+        assert_eq!(data[4 * 11], 255);
+    }
+
+    #[test]
+    fn drop_shadow_offset() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        data[4 * 5..4 * 6].copy_from_slice(&[255, 0, 0, 255]);
+
+        // shadow down and to the right, with no blur
+        Filter::drop_shadow([1., -1.], 0., Color(0x000000ff)).apply_rgba(&mut data, 4, 4, 2.);
+
+        assert_eq!(&data[4 * 5..4 * 6], &[255, 0, 0, 255]);
+        assert_eq!(&data[4 * 15..4 * 16], &[0, 0, 0, 255]);
+        assert_eq!(data[4 * 10 + 3], 0);
+    }
+}
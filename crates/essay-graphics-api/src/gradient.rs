@@ -0,0 +1,280 @@
+use std::str::FromStr;
+
+use crate::{path_opt::StyleErr, Color, Point};
+
+///
+/// Linear or radial gradient paint for filling or stroking a path.
+///
+/// Gradient geometry is in the path's coordinates. Color stops are offsets
+/// in the gradient's parameter space where 0 is the start point (or center)
+/// and 1 is the end point (or radius).
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct Gradient {
+    shape: GradientShape,
+    stops: Vec<(f32, Color)>,
+    spread: Spread,
+    interpolation: Interpolation,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GradientShape {
+    Linear(Point, Point),
+    Radial(Point, f32),
+}
+
+impl Gradient {
+    pub fn linear(p0: impl Into<Point>, p1: impl Into<Point>) -> Self {
+        Self::new(GradientShape::Linear(p0.into(), p1.into()))
+    }
+
+    pub fn radial(center: impl Into<Point>, radius: f32) -> Self {
+        assert!(radius > 0., "gradient radius must be positive {}", radius);
+
+        Self::new(GradientShape::Radial(center.into(), radius))
+    }
+
+    fn new(shape: GradientShape) -> Self {
+        Self {
+            shape,
+            stops: Vec::new(),
+            spread: Spread::Pad,
+            interpolation: Interpolation::LinearRgb,
+        }
+    }
+
+    ///
+    /// Adds a color stop. Stops are kept sorted by offset.
+    ///
+    pub fn stop(mut self, offset: f32, color: impl Into<Color>) -> Self {
+        let i = self.stops.partition_point(|(t, _)| *t <= offset);
+
+        self.stops.insert(i, (offset, color.into()));
+
+        self
+    }
+
+    pub fn spread(mut self, spread: impl Into<Spread>) -> Self {
+        self.spread = spread.into();
+
+        self
+    }
+
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+
+        self
+    }
+
+    #[inline]
+    pub fn get_shape(&self) -> &GradientShape {
+        &self.shape
+    }
+
+    #[inline]
+    pub fn get_stops(&self) -> &Vec<(f32, Color)> {
+        &self.stops
+    }
+
+    #[inline]
+    pub fn get_spread(&self) -> Spread {
+        self.spread
+    }
+
+    #[inline]
+    pub fn get_interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    ///
+    /// Gradient parameter at a point before applying the spread mode.
+    ///
+    pub fn param(&self, p: Point) -> f32 {
+        match self.shape {
+            GradientShape::Linear(p0, p1) => {
+                let (dx, dy) = (p1.x() - p0.x(), p1.y() - p0.y());
+                let len_sq = dx * dx + dy * dy;
+
+                if len_sq > f32::EPSILON {
+                    ((p.x() - p0.x()) * dx + (p.y() - p0.y()) * dy) / len_sq
+                } else {
+                    0.
+                }
+            }
+            GradientShape::Radial(center, radius) => {
+                center.dist(&p) / radius
+            }
+        }
+    }
+
+    ///
+    /// Color at gradient parameter t, after applying the spread mode.
+    ///
+    pub fn color_at(&self, t: f32) -> Color {
+        self.interpolate(self.spread.apply(t))
+    }
+
+    ///
+    /// Samples the gradient at n evenly spaced points over [0, 1] for
+    /// renderers using a lookup table. The renderer applies the spread mode.
+    ///
+    pub fn to_lut(&self, n: usize) -> Vec<Color> {
+        assert!(n > 1);
+
+        (0..n).map(|i| {
+            self.interpolate(i as f32 / (n - 1) as f32)
+        }).collect()
+    }
+
+    fn interpolate(&self, t: f32) -> Color {
+        let stops = &self.stops;
+
+        if stops.len() == 0 {
+            return Color::none();
+        }
+
+        let i = stops.partition_point(|(offset, _)| *offset <= t);
+
+        if i == 0 {
+            return stops[0].1;
+        } else if i == stops.len() {
+            return stops[i - 1].1;
+        }
+
+        let (t0, c0) = stops[i - 1];
+        let (t1, c1) = stops[i];
+
+        let s = if t1 - t0 > f32::EPSILON {
+            (t - t0) / (t1 - t0)
+        } else {
+            1.
+        };
+
+        self.interpolation.mix(c0, c1, s)
+    }
+}
+
+///
+/// Gradient behavior outside of [0, 1].
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Spread {
+    /// Extends the end colors
+    Pad,
+    /// Restarts the gradient
+    Repeat,
+    /// Alternates between the gradient and its mirror image
+    Reflect,
+}
+
+impl Spread {
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Spread::Pad => t.clamp(0., 1.),
+            Spread::Repeat => t.rem_euclid(1.),
+            Spread::Reflect => 1. - (t.rem_euclid(2.) - 1.).abs(),
+        }
+    }
+}
+
+impl FromStr for Spread {
+    type Err = StyleErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "pad" => Ok(Self::Pad),
+            "repeat" => Ok(Self::Repeat),
+            "reflect" => Ok(Self::Reflect),
+            _ => Err(StyleErr(format!("'{}' is an unknown spread", name)))
+        }
+    }
+}
+
+impl From<&str> for Spread {
+    fn from(name: &str) -> Self {
+        match Self::from_str(name) {
+            Ok(spread) => spread,
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+}
+
+///
+/// Color space for interpolating between gradient stops.
+///
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Interpolation {
+    LinearRgb,
+    Lab,
+}
+
+impl Interpolation {
+    pub fn mix(&self, c0: Color, c1: Color, s: f32) -> Color {
+        let alpha = c0.alpha() + s * (c1.alpha() - c0.alpha());
+
+        match self {
+            Interpolation::LinearRgb => {
+                let [r0, g0, b0, _] = c0.to_lrgb();
+                let [r1, g1, b1, _] = c1.to_lrgb();
+
+                Color::from_rgba(
+                    Color::lrgb_to_srgb(r0 + s * (r1 - r0)),
+                    Color::lrgb_to_srgb(g0 + s * (g1 - g0)),
+                    Color::lrgb_to_srgb(b0 + s * (b1 - b0)),
+                    alpha,
+                )
+            }
+            Interpolation::Lab => {
+                let [l0, a0, b0] = c0.to_lab();
+                let [l1, a1, b1] = c1.to_lab();
+
+                Color::from_lab(
+                    l0 + s * (l1 - l0),
+                    a0 + s * (a1 - a0),
+                    b0 + s * (b1 - b0),
+                ).set_alpha(alpha)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, Point};
+
+    use super::{Gradient, Spread};
+
+    #[test]
+    fn linear_param() {
+        let gradient = Gradient::linear([0., 0.], [10., 0.]);
+
+        assert_eq!(gradient.param(Point(5., 3.)), 0.5);
+        assert_eq!(gradient.param(Point(20., 0.)), 2.);
+
+        let gradient = Gradient::radial([0., 0.], 2.);
+
+        assert_eq!(gradient.param(Point(0., 1.)), 0.5);
+    }
+
+    #[test]
+    fn spread() {
+        assert_eq!(Spread::Pad.apply(1.25), 1.);
+        assert_eq!(Spread::Repeat.apply(1.25), 0.25);
+        assert_eq!(Spread::Reflect.apply(1.25), 0.75);
+        assert_eq!(Spread::Reflect.apply(-0.25), 0.25);
+    }
+
+    #[test]
+    fn color_at() {
+        let gradient = Gradient::linear([0., 0.], [1., 0.])
+            .stop(1., Color(0xffffffff))
+            .stop(0., Color(0x000000ff));
+
+        assert_eq!(gradient.color_at(-1.), Color(0x000000ff));
+        assert_eq!(gradient.color_at(0.), Color(0x000000ff));
+        assert_eq!(gradient.color_at(1.), Color(0xffffffff));
+
+        // mid-point in linear rgb is lighter than sRGB 0x80
+        assert!(gradient.color_at(0.5).red() > 0.7);
+    }
+}
@@ -0,0 +1,342 @@
+use std::{f32::consts::{PI, TAU}, str::FromStr};
+
+use crate::path_opt::StyleErr;
+
+///
+/// Hatch fill for a closed path, combining one or more patterns, each with
+/// a density. A density of 2 doubles the number of lines, like matplotlib's
+/// "//" compared to "/".
+///
+#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+pub struct Hatch {
+    density: [u8; HatchPattern::COUNT],
+}
+
+impl Hatch {
+    /// Vertical lines, the former Hatch::Vertical
+    pub const VERTICAL: Hatch = Hatch::new().pattern(HatchPattern::Vertical, 1);
+
+    /// Horizontal lines, the former Hatch::Horizontal
+    pub const HORIZONTAL: Hatch = Hatch::new().pattern(HatchPattern::Horizontal, 1);
+
+    #[deprecated(note = "Hatch is now a struct, use Hatch::VERTICAL")]
+    #[allow(non_upper_case_globals)]
+    pub const Vertical: Hatch = Hatch::VERTICAL;
+
+    #[deprecated(note = "Hatch is now a struct, use Hatch::HORIZONTAL")]
+    #[allow(non_upper_case_globals)]
+    pub const Horizontal: Hatch = Hatch::HORIZONTAL;
+
+    pub const fn new() -> Self {
+        Self {
+            density: [0; HatchPattern::COUNT],
+        }
+    }
+
+    ///
+    /// Adds a pattern to the hatch, increasing its density if it's already
+    /// present.
+    ///
+    pub const fn pattern(mut self, pattern: HatchPattern, density: u8) -> Self {
+        let i = pattern.index();
+
+        self.density[i] = self.density[i].saturating_add(density);
+
+        self
+    }
+
+    #[inline]
+    pub fn density(&self, pattern: HatchPattern) -> u8 {
+        self.density[pattern.index()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.density.iter().all(|d| *d == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=(HatchPattern, u8)> + '_ {
+        HatchPattern::ALL.iter()
+            .map(|pattern| (*pattern, self.density(*pattern)))
+            .filter(|(_, density)| *density > 0)
+    }
+
+    ///
+    /// Rasterizes a square, tileable coverage mask of the hatch, where size
+    /// is the tile size and line_width is the hatch line width, both in
+    /// pixels. Each pixel is supersampled for antialiasing.
+    ///
+    pub fn to_mask(&self, size: usize, line_width: f32) -> Vec<u8> {
+        let n_sub = 4;
+        let mut data = Vec::with_capacity(size * size);
+
+        for j in 0..size {
+            for i in 0..size {
+                let mut count = 0;
+
+                for sj in 0..n_sub {
+                    for si in 0..n_sub {
+                        let x = i as f32 + (si as f32 + 0.5) / n_sub as f32;
+                        let y = j as f32 + (sj as f32 + 0.5) / n_sub as f32;
+
+                        if self.is_covered(x, y, size as f32, line_width) {
+                            count += 1;
+                        }
+                    }
+                }
+
+                data.push((255 * count / (n_sub * n_sub)) as u8);
+            }
+        }
+
+        data
+    }
+
+    fn is_covered(&self, x: f32, y: f32, size: f32, line_width: f32) -> bool {
+        self.iter().any(|(pattern, density)| {
+            // density 1 is two lines per tile, so tiles join seamlessly
+            let spacing = size / (2. * density as f32);
+
+            pattern.is_covered(x, y, spacing, line_width)
+        })
+    }
+}
+
+impl From<HatchPattern> for Hatch {
+    fn from(pattern: HatchPattern) -> Self {
+        Hatch::new().pattern(pattern, 1)
+    }
+}
+
+impl FromStr for Hatch {
+    type Err = StyleErr;
+
+    ///
+    /// Parses a pattern name like "diagonal" or matplotlib-style pattern
+    /// characters like "//" or "x.", where repeated characters increase the
+    /// density.
+    ///
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        if let Ok(pattern) = HatchPattern::from_str(name) {
+            return Ok(pattern.into());
+        }
+
+        let mut hatch = Hatch::new();
+
+        for ch in name.chars() {
+            let pattern = match ch {
+                '|' => HatchPattern::Vertical,
+                '-' => HatchPattern::Horizontal,
+                '/' => HatchPattern::Diagonal,
+                '\\' => HatchPattern::BackDiagonal,
+                '+' => HatchPattern::Cross,
+                'x' | 'X' => HatchPattern::DiagonalCross,
+                '.' => HatchPattern::Dots,
+                '*' => HatchPattern::Stars,
+                _ => {
+                    return Err(StyleErr(format!("'{}' is an unknown hatch", name)))
+                }
+            };
+
+            hatch = hatch.pattern(pattern, 1);
+        }
+
+        if hatch.is_empty() {
+            Err(StyleErr(format!("'{}' is an unknown hatch", name)))
+        } else {
+            Ok(hatch)
+        }
+    }
+}
+
+impl From<&str> for Hatch {
+    fn from(name: &str) -> Self {
+        match Self::from_str(name) {
+            Ok(hatch) => hatch,
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+pub enum HatchPattern {
+    Vertical,
+    Horizontal,
+    Diagonal,
+    BackDiagonal,
+    Cross,
+    DiagonalCross,
+    Dots,
+    Stars,
+}
+
+impl HatchPattern {
+    const COUNT: usize = 8;
+
+    pub const ALL: [HatchPattern; HatchPattern::COUNT] = [
+        HatchPattern::Vertical,
+        HatchPattern::Horizontal,
+        HatchPattern::Diagonal,
+        HatchPattern::BackDiagonal,
+        HatchPattern::Cross,
+        HatchPattern::DiagonalCross,
+        HatchPattern::Dots,
+        HatchPattern::Stars,
+    ];
+
+    const fn index(&self) -> usize {
+        *self as usize
+    }
+
+    ///
+    /// True if the point is inside the pattern, where spacing is the
+    /// distance between lines or dots.
+    ///
+    pub fn is_covered(&self, x: f32, y: f32, spacing: f32, line_width: f32) -> bool {
+        let lw2 = 0.5 * line_width;
+
+        match self {
+            HatchPattern::Vertical => line_dist(x, spacing) <= lw2,
+            HatchPattern::Horizontal => line_dist(y, spacing) <= lw2,
+            HatchPattern::Diagonal => {
+                // lines along x = y are spaced horizontally
+                line_dist(x - y, spacing) * 0.5f32.sqrt() <= lw2
+            }
+            HatchPattern::BackDiagonal => {
+                line_dist(x + y, spacing) * 0.5f32.sqrt() <= lw2
+            }
+            HatchPattern::Cross => {
+                HatchPattern::Vertical.is_covered(x, y, spacing, line_width)
+                || HatchPattern::Horizontal.is_covered(x, y, spacing, line_width)
+            }
+            HatchPattern::DiagonalCross => {
+                HatchPattern::Diagonal.is_covered(x, y, spacing, line_width)
+                || HatchPattern::BackDiagonal.is_covered(x, y, spacing, line_width)
+            }
+            HatchPattern::Dots => {
+                let r = lw2.max(0.1 * spacing);
+
+                let dx = line_dist(x, spacing);
+                let dy = line_dist(y, spacing);
+
+                dx * dx + dy * dy <= r * r
+            }
+            HatchPattern::Stars => {
+                let r = 0.3 * spacing;
+
+                let dx = offset(x, spacing);
+                let dy = offset(y, spacing);
+
+                is_in_star(dx, dy, r)
+            }
+        }
+    }
+}
+
+impl FromStr for HatchPattern {
+    type Err = StyleErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "vertical" => Ok(Self::Vertical),
+            "horizontal" => Ok(Self::Horizontal),
+            "diagonal" => Ok(Self::Diagonal),
+            "back_diagonal" => Ok(Self::BackDiagonal),
+            "cross" => Ok(Self::Cross),
+            "diagonal_cross" => Ok(Self::DiagonalCross),
+            "dots" => Ok(Self::Dots),
+            "stars" => Ok(Self::Stars),
+            _ => Err(StyleErr(format!("'{}' is an unknown hatch pattern", name)))
+        }
+    }
+}
+
+// signed offset from the nearest line, where lines are centered in each cell
+fn offset(x: f32, spacing: f32) -> f32 {
+    let u = (x - 0.5 * spacing).rem_euclid(spacing);
+
+    if u > 0.5 * spacing {
+        u - spacing
+    } else {
+        u
+    }
+}
+
+fn line_dist(x: f32, spacing: f32) -> f32 {
+    offset(x, spacing).abs()
+}
+
+// five-pointed star pointing up with outer radius r
+fn is_in_star(x: f32, y: f32, r: f32) -> bool {
+    let rho = x.hypot(y);
+
+    if rho > r {
+        return false;
+    }
+
+    let sector = TAU / 5.;
+    let inner = r * 0.382;
+
+    // fold into the half sector between an outer and an inner vertex
+    let theta = (y.atan2(x) - 0.5 * PI).rem_euclid(sector);
+    let psi = (theta - 0.5 * sector).abs();
+    let psi = 0.5 * sector - psi;
+
+    let (px, py) = (rho * psi.cos(), rho * psi.sin());
+    let (ox, oy) = (r, 0.);
+    let (ix, iy) = (inner * (0.5 * sector).cos(), inner * (0.5 * sector).sin());
+
+    // the point is inside if it's on the origin's side of the outer edge
+    let side = |x: f32, y: f32| (ix - ox) * (y - oy) - (iy - oy) * (x - ox);
+
+    side(px, py) * side(0., 0.) >= 0.
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{Hatch, HatchPattern};
+
+    #[test]
+    fn from_str() {
+        assert_eq!(Hatch::from("/"), Hatch::from(HatchPattern::Diagonal));
+        assert_eq!(Hatch::from("////").density(HatchPattern::Diagonal), 4);
+        assert_eq!(Hatch::from("cross"), Hatch::from(HatchPattern::Cross));
+
+        let hatch = Hatch::from("x.");
+        assert_eq!(hatch.density(HatchPattern::DiagonalCross), 1);
+        assert_eq!(hatch.density(HatchPattern::Dots), 1);
+        assert_eq!(hatch.density(HatchPattern::Vertical), 0);
+
+        assert_eq!(Hatch::VERTICAL, Hatch::from("|"));
+        assert_eq!(Hatch::HORIZONTAL, Hatch::from("horizontal"));
+
+        assert!(Hatch::from_str("q").is_err());
+        assert!(Hatch::from_str("").is_err());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_names() {
+        assert_eq!(Hatch::Vertical, Hatch::VERTICAL);
+        assert_eq!(Hatch::Horizontal, Hatch::HORIZONTAL);
+    }
+
+    #[test]
+    fn vertical_mask() {
+        let mask = Hatch::from("|").to_mask(8, 2.);
+
+        // lines centered at x = 2 and x = 6
+        assert_eq!(&mask[0..8], &[0, 255, 255, 0, 0, 255, 255, 0]);
+        assert_eq!(&mask[56..64], &[0, 255, 255, 0, 0, 255, 255, 0]);
+    }
+
+    #[test]
+    fn star() {
+        let hatch = Hatch::from("*");
+
+        assert!(HatchPattern::Stars.is_covered(16., 16., 32., 1.));
+        assert!(! HatchPattern::Stars.is_covered(0., 0., 32., 1.));
+        assert!(! hatch.is_empty());
+    }
+}
@@ -0,0 +1,767 @@
+use std::{collections::HashMap, f32::consts::PI, time::Duration};
+
+use crate::{
+    renderer::{Canvas, Event, Key, KeyEvent, KeyState, Modifiers, MouseButton, ScrollDelta},
+    Bounds, Point,
+};
+
+///
+/// Button mapping and thresholds for interaction gestures.
+///
+/// By default, left drag pans, right drag selects a zoom box, and a left
+/// double click resets the view. For touch, one finger pans, two fingers
+/// pinch and rotate, a tap is a left click, a double tap resets the view,
+/// and a long press is a right click.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gestures {
+    pan_button: Option<MouseButton>,
+    zoom_button: Option<MouseButton>,
+    reset_button: Option<MouseButton>,
+
+    drag_min: f32,
+    zoom_min: f32,
+    double_click: Duration,
+    long_press: Duration,
+}
+
+impl Gestures {
+    pub fn new() -> Self {
+        Self {
+            pan_button: Some(MouseButton::Left),
+            zoom_button: Some(MouseButton::Right),
+            reset_button: Some(MouseButton::Left),
+
+            drag_min: 20.,
+            zoom_min: 20.,
+            double_click: Duration::from_millis(500),
+            long_press: Duration::from_millis(500),
+        }
+    }
+
+    ///
+    /// Button whose drag pans the view, or None to disable panning.
+    ///
+    pub fn pan_button(&mut self, button: Option<MouseButton>) -> &mut Self {
+        self.pan_button = button;
+
+        self
+    }
+
+    ///
+    /// Button whose drag selects a zoom box, or None to disable zooming.
+    ///
+    pub fn zoom_button(&mut self, button: Option<MouseButton>) -> &mut Self {
+        self.zoom_button = button;
+
+        self
+    }
+
+    ///
+    /// Button whose double click resets the view, or None to disable.
+    ///
+    pub fn reset_button(&mut self, button: Option<MouseButton>) -> &mut Self {
+        self.reset_button = button;
+
+        self
+    }
+
+    ///
+    /// Distance in pixels the cursor must move before a press is a drag.
+    ///
+    pub fn drag_min(&mut self, drag_min: f32) -> &mut Self {
+        assert!(drag_min >= 0., "drag_min must not be negative {}", drag_min);
+
+        self.drag_min = drag_min;
+
+        self
+    }
+
+    ///
+    /// Minimum zoom box diagonal in pixels, so a click isn't a zoom.
+    ///
+    pub fn zoom_min(&mut self, zoom_min: f32) -> &mut Self {
+        assert!(zoom_min >= 0., "zoom_min must not be negative {}", zoom_min);
+
+        self.zoom_min = zoom_min;
+
+        self
+    }
+
+    ///
+    /// Maximum time between two presses of a double click.
+    ///
+    pub fn double_click(&mut self, double_click: Duration) -> &mut Self {
+        self.double_click = double_click;
+
+        self
+    }
+
+    ///
+    /// Time a touch must be held without moving to be a long press.
+    ///
+    pub fn long_press(&mut self, long_press: Duration) -> &mut Self {
+        self.long_press = long_press;
+
+        self
+    }
+
+    #[inline]
+    pub fn get_pan_button(&self) -> Option<MouseButton> {
+        self.pan_button
+    }
+
+    #[inline]
+    pub fn get_zoom_button(&self) -> Option<MouseButton> {
+        self.zoom_button
+    }
+
+    #[inline]
+    pub fn get_reset_button(&self) -> Option<MouseButton> {
+        self.reset_button
+    }
+
+    #[inline]
+    pub fn get_drag_min(&self) -> f32 {
+        self.drag_min
+    }
+
+    #[inline]
+    pub fn get_zoom_min(&self) -> f32 {
+        self.zoom_min
+    }
+
+    #[inline]
+    pub fn get_double_click(&self) -> Duration {
+        self.double_click
+    }
+
+    #[inline]
+    pub fn get_long_press(&self) -> Duration {
+        self.long_press
+    }
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Gestures::new()
+    }
+}
+
+///
+/// Platform-independent window input before gesture recognition, with the
+/// cursor in canvas coordinates.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum RawInput {
+    /// Window width and height in pixels
+    Resize(f32, f32),
+    CursorMoved(Point),
+    CursorLeft,
+    ButtonPress(MouseButton),
+    ButtonRelease(MouseButton),
+    Wheel(ScrollDelta),
+    Magnify(f32),
+    /// Trackpad rotation in radians, counterclockwise
+    Rotate(f32),
+    Modifiers(Modifiers),
+    Key(Key, KeyState),
+    /// Touch with a finger id
+    Touch(u64, TouchPhase, Point),
+    /// Time passing without input, for long presses
+    Tick,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    Start,
+    Move,
+    End,
+    Cancel,
+}
+
+///
+/// Active drag gesture, used for the cursor icon.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    None,
+    Pan,
+    Zoom,
+}
+
+///
+/// Converts raw input into events, recognizing drags, double clicks, pans
+/// and zoom boxes. Times are from any fixed start, so synthetic input can
+/// be replayed without a window.
+///
+/// A zoom box's ZoomBounds comes before its button's release event, so a
+/// layout delivers it while the drag's view still holds pointer capture.
+///
+pub struct GestureRecognizer {
+    gestures: Gestures,
+
+    cursor: Point,
+    modifiers: Modifiers,
+    buttons: HashMap<MouseButton, ButtonState>,
+
+    touches: Vec<TouchState>,
+    last_tap: Option<(Duration, Point)>,
+}
+
+impl GestureRecognizer {
+    pub fn new(gestures: Gestures) -> Self {
+        Self {
+            gestures,
+
+            cursor: Point(0., 0.),
+            modifiers: Modifiers::NONE,
+            buttons: HashMap::new(),
+
+            touches: Vec::new(),
+            last_tap: None,
+        }
+    }
+
+    #[inline]
+    pub fn gestures(&self) -> &Gestures {
+        &self.gestures
+    }
+
+    #[inline]
+    pub fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    #[inline]
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    ///
+    /// Active pan or zoom, once the button has moved past drag_min, so a
+    /// click doesn't flash the gesture's cursor.
+    ///
+    pub fn gesture(&self) -> Gesture {
+        if self.is_drag(self.gestures.pan_button) {
+            Gesture::Pan
+        } else if self.is_drag(self.gestures.zoom_button) {
+            Gesture::Zoom
+        } else {
+            Gesture::None
+        }
+    }
+
+    fn is_drag(&self, button: Option<MouseButton>) -> bool {
+        match button {
+            Some(button) => match self.buttons.get(&button) {
+                Some(state) => state.is_pressed && state.is_drag,
+                None => false,
+            },
+            None => false,
+        }
+    }
+
+    ///
+    /// Time when a held touch becomes a long press, when the caller should
+    /// send a Tick if there's no other input.
+    ///
+    pub fn deadline(&self) -> Option<Duration> {
+        match self.touches.as_slice() {
+            [touch] if touch.is_tap() => Some(touch.time + self.gestures.long_press),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Events for the input at a time.
+    ///
+    pub fn input(&mut self, time: Duration, input: &RawInput) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        self.long_press(time, &mut events);
+
+        match input {
+            RawInput::Resize(width, height) => {
+                events.push(Event::Resize(Bounds::<Canvas>::from([*width, *height])));
+            }
+            RawInput::CursorMoved(pos) => self.cursor_moved(*pos, &mut events),
+            RawInput::CursorLeft => events.push(Event::Leave(self.cursor)),
+            RawInput::ButtonPress(button) => self.press(time, *button, &mut events),
+            RawInput::ButtonRelease(button) => self.release(*button, &mut events),
+            RawInput::Wheel(delta) => events.push(Event::MouseWheel(self.cursor, *delta)),
+            RawInput::Magnify(delta) => events.push(Event::Pinch(self.cursor, *delta)),
+            RawInput::Rotate(delta) => events.push(Event::Rotate(self.cursor, *delta)),
+            RawInput::Modifiers(modifiers) => self.modifiers = *modifiers,
+            RawInput::Key(key, state) => self.key(*key, *state, &mut events),
+            RawInput::Touch(id, phase, pos) => {
+                match phase {
+                    TouchPhase::Start => self.touch_start(time, *id, *pos),
+                    TouchPhase::Move => self.touch_move(*id, *pos, &mut events),
+                    TouchPhase::End => self.touch_end(time, *id, *pos, &mut events),
+                    TouchPhase::Cancel => self.touches.retain(|t| t.id != *id),
+                }
+            }
+            RawInput::Tick => {},
+        }
+
+        events
+    }
+
+    fn cursor_moved(&mut self, pos: Point, events: &mut Vec<Event>) {
+        self.cursor = pos;
+
+        events.push(Event::MouseMove(pos));
+
+        // sorted, so chorded drags are sent in a stable order
+        let mut pressed: Vec<MouseButton> = self.buttons.iter()
+            .filter(|(_, state)| state.is_pressed)
+            .map(|(button, _)| *button)
+            .collect();
+        pressed.sort();
+
+        for button in pressed {
+            let is_pan = self.gestures.pan_button == Some(button);
+            let drag_min = self.gestures.drag_min;
+            let modifiers = self.modifiers;
+
+            let state = self.buttons.get_mut(&button).unwrap();
+
+            if ! state.is_drag && drag_min <= state.start.dist(&pos) {
+                state.is_drag = true;
+            }
+
+            if ! state.is_drag {
+                continue;
+            }
+
+            events.push(match button {
+                MouseButton::Left => Event::MouseLeftDrag(state.start, pos, modifiers),
+                MouseButton::Right => Event::MouseRightDrag(state.start, pos, modifiers),
+                MouseButton::Middle => Event::MouseMiddleDrag(state.start, pos, modifiers),
+                _ => Event::MouseDrag(state.start, pos, button, modifiers),
+            });
+
+            if is_pan {
+                events.push(Event::Pan(state.start, state.last, pos));
+            }
+
+            state.last = pos;
+        }
+    }
+
+    fn press(&mut self, time: Duration, button: MouseButton, events: &mut Vec<Event>) {
+        let pos = self.cursor;
+        let modifiers = self.modifiers;
+
+        events.push(match button {
+            MouseButton::Left => Event::MouseLeftPress(pos, modifiers),
+            MouseButton::Right => Event::MouseRightPress(pos, modifiers),
+            MouseButton::Middle => Event::MouseMiddlePress(pos, modifiers),
+            _ => Event::MousePress(pos, button, modifiers),
+        });
+
+        let state = self.buttons.entry(button).or_insert(ButtonState::new());
+
+        let is_double = match state.press_time {
+            Some(last) => time.saturating_sub(last) < self.gestures.double_click,
+            None => false,
+        };
+
+        state.is_pressed = true;
+        state.is_drag = false;
+        state.start = pos;
+        state.last = pos;
+
+        if is_double {
+            // a third click starts a new double click
+            state.press_time = None;
+
+            match button {
+                MouseButton::Right => events.push(Event::MouseRightDoubleClick(pos)),
+                MouseButton::Middle => events.push(Event::MouseMiddleDoubleClick(pos)),
+                _ => {}
+            }
+
+            if self.gestures.reset_button == Some(button) {
+                events.push(Event::ResetView(pos));
+            }
+        } else {
+            state.press_time = Some(time);
+        }
+    }
+
+    fn release(&mut self, button: MouseButton, events: &mut Vec<Event>) {
+        let pos = self.cursor;
+        let modifiers = self.modifiers;
+
+        // zoom comes before the release, which ends a layout's pointer
+        // capture, so it goes to the view where the drag started
+        if let Some(state) = self.buttons.get_mut(&button) {
+            if state.is_pressed
+                && self.gestures.zoom_button == Some(button)
+                && self.gestures.zoom_min <= state.start.dist(&pos) {
+                events.push(Event::ZoomBounds(state.start, pos));
+            }
+
+            state.is_pressed = false;
+            state.is_drag = false;
+        }
+
+        events.push(match button {
+            MouseButton::Left => Event::MouseLeftRelease(pos, modifiers),
+            MouseButton::Right => Event::MouseRightRelease(pos, modifiers),
+            MouseButton::Middle => Event::MouseMiddleRelease(pos, modifiers),
+            _ => Event::MouseRelease(pos, button, modifiers),
+        });
+    }
+
+    fn key(&mut self, key: Key, state: KeyState, events: &mut Vec<Event>) {
+        let pos = self.cursor;
+
+        events.push(Event::Key(pos, KeyEvent::new(key, state, self.modifiers)));
+
+        if state != KeyState::Release {
+            let ch = match key {
+                Key::Char(ch) => Some(ch),
+                Key::Space => Some(' '),
+                Key::Tab => Some('\t'),
+                Key::Enter => Some('\n'),
+                _ => None,
+            };
+
+            if let Some(ch) = ch {
+                events.push(Event::KeyPress(pos, ch));
+            }
+        }
+    }
+}
+
+impl GestureRecognizer {
+    fn touch_start(&mut self, time: Duration, id: u64, pos: Point) {
+        self.touches.push(TouchState {
+            id,
+            start: pos,
+            last: pos,
+            time,
+            is_drag: false,
+            is_multi: false,
+            is_long: false,
+        });
+
+        // a second finger ends any single-finger gesture for all fingers
+        if self.touches.len() > 1 {
+            for touch in &mut self.touches {
+                touch.is_multi = true;
+            }
+        }
+    }
+
+    fn touch_move(&mut self, id: u64, pos: Point, events: &mut Vec<Event>) {
+        let i = match self.touches.iter().position(|t| t.id == id) {
+            Some(i) => i,
+            None => return,
+        };
+
+        if self.touches.len() == 1 {
+            let drag_min = self.gestures.drag_min;
+            let touch = &mut self.touches[0];
+
+            if touch.is_multi || touch.is_long {
+                touch.last = pos;
+                return;
+            }
+
+            if ! touch.is_drag && drag_min <= touch.start.dist(&pos) {
+                touch.is_drag = true;
+            }
+
+            if touch.is_drag {
+                events.push(Event::Pan(touch.start, touch.last, pos));
+            }
+
+            touch.last = pos;
+        } else if i < 2 {
+            let (p0, p1) = (self.touches[0].last, self.touches[1].last);
+            self.touches[i].last = pos;
+            let (q0, q1) = (self.touches[0].last, self.touches[1].last);
+
+            let center = Point(0.5 * (q0.x() + q1.x()), 0.5 * (q0.y() + q1.y()));
+
+            let dist = p0.dist(&p1);
+
+            if dist > 0. {
+                events.push(Event::Pinch(center, q0.dist(&q1) / dist - 1.));
+            }
+
+            let angle = (q1.y() - q0.y()).atan2(q1.x() - q0.x())
+                - (p1.y() - p0.y()).atan2(p1.x() - p0.x());
+
+            // shortest turn, so crossing the -pi, pi boundary isn't a full turn
+            let angle = (angle + PI).rem_euclid(2. * PI) - PI;
+
+            if angle != 0. {
+                events.push(Event::Rotate(center, angle));
+            }
+        } else {
+            self.touches[i].last = pos;
+        }
+    }
+
+    fn touch_end(&mut self, time: Duration, id: u64, pos: Point, events: &mut Vec<Event>) {
+        let touch = match self.touches.iter().position(|t| t.id == id) {
+            Some(i) => self.touches.remove(i),
+            None => return,
+        };
+
+        if ! touch.is_tap() {
+            return;
+        }
+
+        events.push(Event::MouseLeftPress(pos, self.modifiers));
+        events.push(Event::MouseLeftRelease(pos, self.modifiers));
+
+        match self.last_tap {
+            Some((last_time, last_pos))
+                if time.saturating_sub(last_time) < self.gestures.double_click
+                && last_pos.dist(&pos) < self.gestures.drag_min => {
+                self.last_tap = None;
+
+                events.push(Event::ResetView(pos));
+            }
+            _ => {
+                self.last_tap = Some((time, pos));
+            }
+        }
+    }
+
+    fn long_press(&mut self, time: Duration, events: &mut Vec<Event>) {
+        if let Some(deadline) = self.deadline() {
+            if deadline <= time {
+                let touch = &mut self.touches[0];
+                touch.is_long = true;
+
+                events.push(Event::MouseRightPress(touch.start, self.modifiers));
+                events.push(Event::MouseRightRelease(touch.start, self.modifiers));
+            }
+        }
+    }
+}
+
+struct TouchState {
+    id: u64,
+    start: Point,
+    last: Point,
+    time: Duration,
+
+    is_drag: bool,
+    is_multi: bool,
+    is_long: bool,
+}
+
+impl TouchState {
+    // still a tap or a long press candidate
+    fn is_tap(&self) -> bool {
+        ! self.is_drag && ! self.is_multi && ! self.is_long
+    }
+}
+
+struct ButtonState {
+    is_pressed: bool,
+    is_drag: bool,
+    start: Point,
+    last: Point,
+    press_time: Option<Duration>,
+}
+
+impl ButtonState {
+    fn new() -> Self {
+        Self {
+            is_pressed: false,
+            is_drag: false,
+            start: Point(0., 0.),
+            last: Point(0., 0.),
+            press_time: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{renderer::{Event, MouseButton}, Point};
+
+    use super::{Gesture, Gestures, GestureRecognizer, RawInput, TouchPhase};
+
+    fn run(gestures: Gestures, inputs: &[(u64, RawInput)]) -> Vec<String> {
+        let mut recognizer = GestureRecognizer::new(gestures);
+        let mut events = Vec::new();
+
+        for (ms, input) in inputs {
+            for event in recognizer.input(Duration::from_millis(*ms), input) {
+                if ! matches!(event, Event::MouseMove(_)) {
+                    events.push(format!("{:?}", event));
+                }
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn pan_and_zoom() {
+        let events = run(Gestures::new(), &[
+            (0, RawInput::CursorMoved(Point(10., 10.))),
+            (0, RawInput::ButtonPress(MouseButton::Left)),
+            (10, RawInput::CursorMoved(Point(20., 10.))),
+            (20, RawInput::CursorMoved(Point(40., 10.))),
+            (30, RawInput::ButtonRelease(MouseButton::Left)),
+            (1000, RawInput::ButtonPress(MouseButton::Right)),
+            (1100, RawInput::CursorMoved(Point(80., 50.))),
+            (1200, RawInput::ButtonRelease(MouseButton::Right)),
+        ]);
+
+        assert_eq!(events, vec![
+            "MouseLeftPress(Point(10.0, 10.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseLeftDrag(Point(10.0, 10.0), Point(40.0, 10.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "Pan(Point(10.0, 10.0), Point(10.0, 10.0), Point(40.0, 10.0))",
+            "MouseLeftRelease(Point(40.0, 10.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseRightPress(Point(40.0, 10.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseRightDrag(Point(40.0, 10.0), Point(80.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "ZoomBounds(Point(40.0, 10.0), Point(80.0, 50.0))",
+            "MouseRightRelease(Point(80.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+        ]);
+    }
+
+    #[test]
+    fn gesture() {
+        let mut recognizer = GestureRecognizer::new(Gestures::new());
+        let mut input = |ms: u64, input: RawInput| {
+            recognizer.input(Duration::from_millis(ms), &input);
+
+            recognizer.gesture()
+        };
+
+        // a click isn't a pan until it moves past drag_min
+        assert_eq!(input(0, RawInput::ButtonPress(MouseButton::Left)), Gesture::None);
+        assert_eq!(input(10, RawInput::CursorMoved(Point(5., 0.))), Gesture::None);
+        assert_eq!(input(20, RawInput::CursorMoved(Point(30., 0.))), Gesture::Pan);
+        assert_eq!(input(30, RawInput::ButtonRelease(MouseButton::Left)), Gesture::None);
+
+        assert_eq!(input(1000, RawInput::ButtonPress(MouseButton::Right)), Gesture::None);
+        assert_eq!(input(1010, RawInput::CursorMoved(Point(30., 30.))), Gesture::Zoom);
+        assert_eq!(input(1020, RawInput::ButtonRelease(MouseButton::Right)), Gesture::None);
+    }
+
+    #[test]
+    fn remap_buttons() {
+        let mut gestures = Gestures::new();
+        gestures
+            .pan_button(Some(MouseButton::Middle))
+            .zoom_button(None)
+            .reset_button(Some(MouseButton::Right))
+            .double_click(Duration::from_millis(200));
+
+        let events = run(gestures, &[
+            (0, RawInput::ButtonPress(MouseButton::Right)),
+            (10, RawInput::CursorMoved(Point(50., 0.))),
+            (20, RawInput::ButtonRelease(MouseButton::Right)),
+            (100, RawInput::ButtonPress(MouseButton::Right)),
+            (110, RawInput::ButtonRelease(MouseButton::Right)),
+            (500, RawInput::ButtonPress(MouseButton::Middle)),
+            (510, RawInput::CursorMoved(Point(0., 0.))),
+        ]);
+
+        let events: Vec<&str> = events.iter()
+            .map(|e| &e[..e.find('(').unwrap()])
+            .collect();
+
+        assert_eq!(events, vec![
+            "MouseRightPress",
+            "MouseRightDrag",
+            "MouseRightRelease",
+            "MouseRightPress",
+            "MouseRightDoubleClick",
+            "ResetView",
+            "MouseRightRelease",
+            "MouseMiddlePress",
+            "MouseMiddleDrag",
+            "Pan",
+        ]);
+    }
+
+    #[test]
+    fn pan_side_button() {
+        let mut gestures = Gestures::new();
+        gestures.pan_button(Some(MouseButton::Back));
+
+        let events = run(gestures, &[
+            (0, RawInput::ButtonPress(MouseButton::Back)),
+            (10, RawInput::CursorMoved(Point(50., 0.))),
+            (20, RawInput::ButtonRelease(MouseButton::Back)),
+        ]);
+
+        assert_eq!(events, vec![
+            "MousePress(Point(0.0, 0.0), Back, Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseDrag(Point(0.0, 0.0), Point(50.0, 0.0), Back, Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "Pan(Point(0.0, 0.0), Point(0.0, 0.0), Point(50.0, 0.0))",
+            "MouseRelease(Point(50.0, 0.0), Back, Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+        ]);
+    }
+
+    #[test]
+    fn touch() {
+        let events = run(Gestures::new(), &[
+            // tap, then a double tap
+            (0, RawInput::Touch(1, TouchPhase::Start, Point(10., 10.))),
+            (50, RawInput::Touch(1, TouchPhase::End, Point(10., 10.))),
+            (200, RawInput::Touch(2, TouchPhase::Start, Point(12., 10.))),
+            (250, RawInput::Touch(2, TouchPhase::End, Point(12., 10.))),
+            // long press
+            (1000, RawInput::Touch(3, TouchPhase::Start, Point(50., 50.))),
+            (1600, RawInput::Tick),
+            (1700, RawInput::Touch(3, TouchPhase::End, Point(50., 50.))),
+            // pan
+            (2000, RawInput::Touch(4, TouchPhase::Start, Point(0., 0.))),
+            (2100, RawInput::Touch(4, TouchPhase::Move, Point(30., 0.))),
+            (2200, RawInput::Touch(4, TouchPhase::End, Point(30., 0.))),
+            // pinch out and rotate a quarter turn
+            (3000, RawInput::Touch(5, TouchPhase::Start, Point(0., 0.))),
+            (3000, RawInput::Touch(6, TouchPhase::Start, Point(10., 0.))),
+            (3100, RawInput::Touch(6, TouchPhase::Move, Point(0., 20.))),
+            (3200, RawInput::Touch(5, TouchPhase::End, Point(0., 0.))),
+            (3200, RawInput::Touch(6, TouchPhase::End, Point(0., 20.))),
+        ]);
+
+        let events: Vec<&str> = events.iter()
+            .map(|e| &e[..e.find('(').unwrap()])
+            .collect();
+
+        assert_eq!(events, vec![
+            "MouseLeftPress",
+            "MouseLeftRelease",
+            "MouseLeftPress",
+            "MouseLeftRelease",
+            "ResetView",
+            "MouseRightPress",
+            "MouseRightRelease",
+            "Pan",
+            "Pinch",
+            "Rotate",
+        ]);
+
+        let mut recognizer = GestureRecognizer::new(Gestures::new());
+        recognizer.input(Duration::from_millis(0), &RawInput::Touch(5, TouchPhase::Start, Point(0., 0.)));
+        recognizer.input(Duration::from_millis(0), &RawInput::Touch(6, TouchPhase::Start, Point(10., 0.)));
+
+        let events = recognizer.input(
+            Duration::from_millis(10),
+            &RawInput::Touch(6, TouchPhase::Move, Point(0., 20.))
+        );
+
+        assert_eq!(format!("{:?}", events), 
+            "[Pinch(Point(0.0, 10.0), 1.0), Rotate(Point(0.0, 10.0), 1.5707964)]"
+        );
+    }
+}
@@ -0,0 +1,10 @@
+mod gesture;
+mod record;
+
+pub use gesture::{
+    Gesture, GestureRecognizer, Gestures, RawInput, TouchPhase,
+};
+
+pub use record::{
+    InputErr, InputRecorder,
+};
@@ -0,0 +1,325 @@
+use std::{fmt, fs, path::Path, str::FromStr, time::Duration};
+
+use crate::{
+    renderer::{Drawable, Key, KeyState, Modifiers, MouseButton, Renderer, ScrollDelta},
+    Point,
+};
+
+use super::{GestureRecognizer, Gestures, RawInput, TouchPhase};
+
+///
+/// Timed raw input that can be saved to a file and replayed against a
+/// drawable, so interaction bugs can be reproduced without a window.
+///
+/// The file has one input per line, starting with the time in milliseconds,
+/// as in "120 press left" or "135 move 40 80.5".
+///
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecorder {
+    inputs: Vec<(Duration, RawInput)>,
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        Self {
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, time: Duration, input: &RawInput) {
+        self.inputs.push((time, input.clone()));
+    }
+
+    #[inline]
+    pub fn inputs(&self) -> &Vec<(Duration, RawInput)> {
+        &self.inputs
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), InputErr> {
+        match fs::write(path.as_ref(), self.to_string()) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(InputErr(format!("{:?}: {}", path.as_ref(), err))),
+        }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, InputErr> {
+        match fs::read_to_string(path.as_ref()) {
+            Ok(text) => text.parse(),
+            Err(err) => Err(InputErr(format!("{:?}: {}", path.as_ref(), err))),
+        }
+    }
+
+    ///
+    /// Sends the recorded input's events to the drawable, recognizing
+    /// gestures as the main loop would.
+    ///
+    pub fn replay(
+        &self,
+        gestures: &Gestures,
+        drawable: &mut dyn Drawable,
+        renderer: &mut dyn Renderer,
+    ) {
+        let mut recognizer = GestureRecognizer::new(gestures.clone());
+
+        for (time, input) in &self.inputs {
+            for event in recognizer.input(*time, input) {
+                drawable.event(renderer, &event);
+            }
+        }
+    }
+}
+
+impl fmt::Display for InputRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (time, input) in &self.inputs {
+            write!(f, "{} ", time.as_millis())?;
+
+            match input {
+                RawInput::Resize(width, height) => write!(f, "resize {} {}", width, height)?,
+                RawInput::CursorMoved(pos) => write!(f, "move {} {}", pos.x(), pos.y())?,
+                RawInput::CursorLeft => write!(f, "leave")?,
+                RawInput::ButtonPress(button) => write!(f, "press {}", button_name(*button))?,
+                RawInput::ButtonRelease(button) => write!(f, "release {}", button_name(*button))?,
+                RawInput::Wheel(ScrollDelta::Line(x, y)) => write!(f, "wheel line {} {}", x, y)?,
+                RawInput::Wheel(ScrollDelta::Pixel(x, y)) => write!(f, "wheel pixel {} {}", x, y)?,
+                RawInput::Magnify(delta) => write!(f, "magnify {}", delta)?,
+                RawInput::Rotate(delta) => write!(f, "rotate {}", delta)?,
+                RawInput::Modifiers(modifiers) => {
+                    write!(f, "modifiers")?;
+
+                    for (name, is_set) in [
+                        ("shift", modifiers.shift),
+                        ("ctrl", modifiers.ctrl),
+                        ("alt", modifiers.alt),
+                        ("super", modifiers.super_key),
+                    ] {
+                        if is_set {
+                            write!(f, " {}", name)?;
+                        }
+                    }
+                }
+                RawInput::Key(key, state) => {
+                    let state = match state {
+                        KeyState::Press => "press",
+                        KeyState::Release => "release",
+                        KeyState::Repeat => "repeat",
+                    };
+
+                    write!(f, "key {} {}", state, key_name(*key))?;
+                }
+                RawInput::Touch(id, phase, pos) => {
+                    let phase = match phase {
+                        TouchPhase::Start => "start",
+                        TouchPhase::Move => "move",
+                        TouchPhase::End => "end",
+                        TouchPhase::Cancel => "cancel",
+                    };
+
+                    write!(f, "touch {} {} {} {}", phase, id, pos.x(), pos.y())?;
+                }
+                RawInput::Tick => write!(f, "tick")?,
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for InputRecorder {
+    type Err = InputErr;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut recorder = InputRecorder::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            match parse_line(line) {
+                Some((time, input)) => recorder.record(time, &input),
+                None => {
+                    return Err(InputErr(format!("line {}: invalid input {:?}", i + 1, line)));
+                }
+            }
+        }
+
+        Ok(recorder)
+    }
+}
+
+fn parse_line(line: &str) -> Option<(Duration, RawInput)> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+
+    let time = Duration::from_millis(args.first()?.parse().ok()?);
+
+    let f = |i: usize| -> Option<f32> { args.get(i)?.parse().ok() };
+
+    let input = match *args.get(1)? {
+        "resize" => RawInput::Resize(f(2)?, f(3)?),
+        "move" => RawInput::CursorMoved(Point(f(2)?, f(3)?)),
+        "leave" => RawInput::CursorLeft,
+        "press" => RawInput::ButtonPress(parse_button(&args[2..])?),
+        "release" => RawInput::ButtonRelease(parse_button(&args[2..])?),
+        "wheel" => match *args.get(2)? {
+            "line" => RawInput::Wheel(ScrollDelta::Line(f(3)?, f(4)?)),
+            "pixel" => RawInput::Wheel(ScrollDelta::Pixel(f(3)?, f(4)?)),
+            _ => return None,
+        },
+        "magnify" => RawInput::Magnify(f(2)?),
+        "rotate" => RawInput::Rotate(f(2)?),
+        "modifiers" => {
+            let mut modifiers = Modifiers::NONE;
+
+            for name in &args[2..] {
+                match *name {
+                    "shift" => modifiers.shift = true,
+                    "ctrl" => modifiers.ctrl = true,
+                    "alt" => modifiers.alt = true,
+                    "super" => modifiers.super_key = true,
+                    _ => return None,
+                }
+            }
+
+            RawInput::Modifiers(modifiers)
+        }
+        "key" => {
+            let state = match *args.get(2)? {
+                "press" => KeyState::Press,
+                "release" => KeyState::Release,
+                "repeat" => KeyState::Repeat,
+                _ => return None,
+            };
+
+            RawInput::Key(parse_key(&args[3..])?, state)
+        }
+        "touch" => {
+            let phase = match *args.get(2)? {
+                "start" => TouchPhase::Start,
+                "move" => TouchPhase::Move,
+                "end" => TouchPhase::End,
+                "cancel" => TouchPhase::Cancel,
+                _ => return None,
+            };
+
+            RawInput::Touch(args.get(3)?.parse().ok()?, phase, Point(f(4)?, f(5)?))
+        }
+        "tick" => RawInput::Tick,
+        _ => return None,
+    };
+
+    Some((time, input))
+}
+
+fn button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Left => "left".to_string(),
+        MouseButton::Right => "right".to_string(),
+        MouseButton::Middle => "middle".to_string(),
+        MouseButton::Back => "back".to_string(),
+        MouseButton::Forward => "forward".to_string(),
+        MouseButton::Other(id) => format!("other {}", id),
+    }
+}
+
+fn parse_button(args: &[&str]) -> Option<MouseButton> {
+    match *args.first()? {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        "back" => Some(MouseButton::Back),
+        "forward" => Some(MouseButton::Forward),
+        "other" => Some(MouseButton::Other(args.get(1)?.parse().ok()?)),
+        _ => None,
+    }
+}
+
+// characters are written as their code point, so space and '#' are safe
+fn key_name(key: Key) -> String {
+    match key {
+        Key::Char(ch) => format!("char {}", ch as u32),
+        Key::F(n) => format!("f {}", n),
+        _ => format!("{:?}", key).to_lowercase(),
+    }
+}
+
+fn parse_key(args: &[&str]) -> Option<Key> {
+    let key = match *args.first()? {
+        "char" => Key::Char(char::from_u32(args.get(1)?.parse().ok()?)?),
+        "f" => Key::F(args.get(1)?.parse().ok()?),
+        "enter" => Key::Enter,
+        "tab" => Key::Tab,
+        "space" => Key::Space,
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "escape" => Key::Escape,
+        "insert" => Key::Insert,
+        "arrowleft" => Key::ArrowLeft,
+        "arrowright" => Key::ArrowRight,
+        "arrowup" => Key::ArrowUp,
+        "arrowdown" => Key::ArrowDown,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "shift" => Key::Shift,
+        "control" => Key::Control,
+        "alt" => Key::Alt,
+        "super" => Key::Super,
+        "other" => Key::Other,
+        _ => return None,
+    };
+
+    Some(key)
+}
+
+#[derive(Clone, Debug)]
+pub struct InputErr(pub String);
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{renderer::{Key, KeyState, Modifiers, MouseButton, ScrollDelta}, Point};
+
+    use super::{InputRecorder, RawInput, TouchPhase};
+
+    #[test]
+    fn round_trip() {
+        let mut recorder = InputRecorder::new();
+
+        let inputs = [
+            RawInput::Resize(800., 600.),
+            RawInput::CursorMoved(Point(10.5, 20.)),
+            RawInput::Modifiers(Modifiers { shift: true, ctrl: true, ..Modifiers::NONE }),
+            RawInput::ButtonPress(MouseButton::Left),
+            RawInput::ButtonRelease(MouseButton::Other(7)),
+            RawInput::Wheel(ScrollDelta::Line(0., -1.)),
+            RawInput::Wheel(ScrollDelta::Pixel(2.5, 12.)),
+            RawInput::Magnify(0.125),
+            RawInput::Key(Key::Char(' '), KeyState::Press),
+            RawInput::Key(Key::F(5), KeyState::Repeat),
+            RawInput::Key(Key::PageDown, KeyState::Release),
+            RawInput::CursorLeft,
+            RawInput::Touch(3, TouchPhase::Start, Point(5., 6.)),
+            RawInput::Touch(3, TouchPhase::Cancel, Point(5., 7.)),
+            RawInput::Rotate(-0.25),
+            RawInput::Tick,
+        ];
+
+        for (i, input) in inputs.iter().enumerate() {
+            recorder.record(Duration::from_millis(10 * i as u64), input);
+        }
+
+        let text = recorder.to_string();
+
+        assert_eq!(text.lines().nth(2), Some("20 modifiers shift ctrl"));
+        assert_eq!(text.parse::<InputRecorder>().unwrap(), recorder);
+
+        assert!("10 press sideways".parse::<InputRecorder>().is_err());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod form;
 pub mod affine2d;
+mod arrow;
 mod image;
 mod clip;
 mod instance;
@@ -8,28 +9,49 @@ mod coord;
 mod bounds;
 mod color;
 mod color_data;
+mod color_vision;
+mod colormap;
+mod filter;
+mod gradient;
+mod hatch;
+pub mod input;
 pub mod renderer;
 mod path;
+mod scalar_map;
 pub mod path_opt;
 pub mod path_style;
 mod text;
 
 pub use affine2d::Affine2d;
 
+pub use arrow::{Arrow, ArrowStyle};
+
 pub use bounds::Bounds;
 
 pub use clip::Clip;
 
-pub use color::{Color, Colors};
+pub use color::{Color, ColorErr, Colors};
+
+pub use color_vision::ColorDeficiency;
+
+pub use colormap::ColorMap;
 
 pub use coord::Coord;
 
+pub use filter::Filter;
+
+pub use gradient::{Gradient, GradientShape, Interpolation, Spread};
+
+pub use hatch::{Hatch, HatchPattern};
+
 pub use path::{
     Path, PathCode,
 };
 
 pub use instance::Instance;
 
+pub use scalar_map::ScalarMap;
+
 pub use image::{
     ImageId, ImageIndex,
 };
@@ -39,7 +61,7 @@ pub use point::{
 };
 
 pub use path_opt::{
-    PathOpt, JoinStyle, CapStyle, LineStyle, TextureId, Hatch,
+    PathOpt, JoinStyle, CapStyle, LineStyle, TextureId,
 };
 
 pub use path_style::PathStyleBase;
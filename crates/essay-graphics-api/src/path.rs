@@ -153,6 +153,80 @@ impl<M: Coord> Path<M> {
         self.transform(&affine2d::rotate_deg(deg))
     }
 
+    ///
+    /// Returns the first point of the path and the unit direction leaving the
+    /// path at that point, i.e. pointing away from the second point.
+    ///
+    pub fn start_tangent(&self) -> Option<(Point, Point)> {
+        let p0 = match self.codes.first() {
+            Some(PathCode::MoveTo(p0)) => *p0,
+            _ => return None,
+        };
+
+        let p1 = match self.codes.get(1) {
+            Some(PathCode::LineTo(p1)) => *p1,
+            Some(PathCode::Bezier2(p1, _)) => *p1,
+            Some(PathCode::Bezier3(p1, _, _)) => *p1,
+            Some(PathCode::ClosePoly(p1)) => *p1,
+            _ => return None,
+        };
+
+        unit_dir(p1, p0).map(|dir| (p0, dir))
+    }
+
+    ///
+    /// Returns the last point of the path and the unit direction of the path
+    /// at that point.
+    ///
+    pub fn end_tangent(&self) -> Option<(Point, Point)> {
+        let len = self.codes.len();
+
+        if len < 2 {
+            return None;
+        }
+
+        let (p0, p1) = match self.codes[len - 1] {
+            PathCode::LineTo(p1) => (self.codes[len - 2].tail(), p1),
+            PathCode::Bezier2(p0, p1) => (p0, p1),
+            PathCode::Bezier3(_, p0, p1) => (p0, p1),
+            _ => return None,
+        };
+
+        unit_dir(p0, p1).map(|dir| (p1, dir))
+    }
+
+    ///
+    /// Returns the path with its start and end pulled back along their
+    /// tangents, for example to leave room for an arrowhead. Each end is
+    /// shortened at most by its segment's length.
+    ///
+    pub fn shorten(&self, start: f32, end: f32) -> Path<M> {
+        let mut codes = self.codes.clone();
+
+        if let (true, Some((p0, dir))) = (start > 0., self.start_tangent()) {
+            let seg_len = p0.dist(&codes[1].tail());
+            let d = start.min(seg_len);
+
+            codes[0] = PathCode::MoveTo(Point(p0.x() - d * dir.x(), p0.y() - d * dir.y()));
+        }
+
+        if let (true, Some((p1, dir))) = (end > 0., self.end_tangent()) {
+            let len = codes.len();
+            let seg_len = p1.dist(&codes[len - 2].tail());
+            let d = end.min(seg_len);
+            let p1 = Point(p1.x() - d * dir.x(), p1.y() - d * dir.y());
+
+            codes[len - 1] = match codes[len - 1] {
+                PathCode::LineTo(_) => PathCode::LineTo(p1),
+                PathCode::Bezier2(c, _) => PathCode::Bezier2(c, p1),
+                PathCode::Bezier3(c1, c2, _) => PathCode::Bezier3(c1, c2, p1),
+                code => code,
+            };
+        }
+
+        Path::new(codes)
+    }
+
     pub fn move_to(x: f32, y: f32) -> PathBuilder<M> {
         PathBuilder::new().move_to(x, y)
     }
@@ -259,6 +333,19 @@ impl<const N: usize, M: Coord> From<[Point; N]> for Path<M> {
     }
 }
 
+fn unit_dir(p0: Point, p1: Point) -> Option<Point> {
+    let dx = p1.x() - p0.x();
+    let dy = p1.y() - p0.y();
+
+    let len = dx.hypot(dy);
+
+    if len > f32::EPSILON {
+        Some(Point(dx / len, dy / len))
+    } else {
+        None
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PathCode {
     MoveTo(Point),
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use super::Color;
+use super::{Arrow, Color, Filter, Gradient, Hatch};
 
 ///
 /// Renderer options for a path, including the fill color, line (edge) color,
@@ -25,6 +25,39 @@ pub trait PathOpt {
     /// 
     fn get_line_style(&self) -> &Option<LineStyle>;
 
+    ///
+    /// Offset (phase) into the dash pattern, in multiples of the line width
+    /// like the pattern itself. Animating the offset moves the dashes 
+    /// along the path.
+    /// 
+    fn get_dash_offset(&self) -> &Option<f32>;
+
+    ///
+    /// Color to fill the gaps of a dashed line. Gaps are transparent
+    /// when None.
+    /// 
+    fn get_gap_color(&self) -> &Option<Color>;
+
+    ///
+    /// Decoration such as an arrowhead at the start of an open path.
+    /// 
+    fn get_arrow_start(&self) -> &Option<Arrow>;
+
+    ///
+    /// Gradient paint for the fill, replacing the face color.
+    /// 
+    fn get_face_gradient(&self) -> &Option<Gradient>;
+
+    ///
+    /// Gradient paint for the line (edge), replacing the edge color.
+    /// 
+    fn get_edge_gradient(&self) -> &Option<Gradient>;
+
+    ///
+    /// Decoration such as an arrowhead at the end of an open path.
+    /// 
+    fn get_arrow_end(&self) -> &Option<Arrow>;
+
     ///
     /// Line width in logical pixels (points for physical dimensions).
     /// 
@@ -35,6 +68,13 @@ pub trait PathOpt {
     /// 
     fn get_join_style(&self) -> &Option<JoinStyle>;
 
+    ///
+    /// Limit on the ratio of a miter length to the line width for a 
+    /// JoinStyle::Miter join. Sharper joins fall back to a bevel. Follows
+    /// the SVG stroke-miterlimit, defaults to 4.
+    /// 
+    fn get_miter_limit(&self) -> &Option<f32>;
+
     ///
     /// Style for a line end, defaults to CapStyle::Butt.
     /// 
@@ -51,11 +91,33 @@ pub trait PathOpt {
     /// 
     fn get_hatch(&self) -> &Option<Hatch>;
 
+    ///
+    /// Color of the hatch lines. When None, the hatch is drawn in the face
+    /// color without a background fill.
+    /// 
+    fn get_hatch_color(&self) -> &Option<Color>;
+
+    ///
+    /// Width of the hatch lines in logical pixels.
+    /// 
+    fn get_hatch_line_width(&self) -> &Option<f32>;
+
     ///
     /// Texture used to fill a closed path.
     /// 
     fn get_texture(&self) -> &Option<TextureId>;
 
+    ///
+    /// Antialiasing for the path edges with a multisampled renderer, 
+    /// defaults to true.
+    /// 
+    fn get_antialias(&self) -> &Option<bool>;
+
+    ///
+    /// Filter such as a blur or drop shadow, applied to the drawn path.
+    /// 
+    fn get_filter(&self) -> &Option<Filter>;
+
     ///
     /// Pushes this style on an option stack. Top styles will override
     /// lower items.
@@ -71,9 +133,6 @@ pub trait PathOpt {
     // clip
     // alpha (forced alpha)
 
-    // antialiased
-    // gapcolor
-    // linestyle
     // dash_cap_style
     // solid_cap_style
 }
@@ -138,6 +197,48 @@ impl PathOpt for Stack<'_> {
         }
     }
 
+    fn get_dash_offset(&self) -> &Option<f32> {
+        match self.next.get_dash_offset() {
+            Some(_) => self.next.get_dash_offset(),
+            None => self.prev.get_dash_offset(),
+        }
+    }
+
+    fn get_gap_color(&self) -> &Option<Color> {
+        match self.next.get_gap_color() {
+            Some(_) => self.next.get_gap_color(),
+            None => self.prev.get_gap_color(),
+        }
+    }
+
+    fn get_face_gradient(&self) -> &Option<Gradient> {
+        match self.next.get_face_gradient() {
+            Some(_) => self.next.get_face_gradient(),
+            None => self.prev.get_face_gradient(),
+        }
+    }
+
+    fn get_edge_gradient(&self) -> &Option<Gradient> {
+        match self.next.get_edge_gradient() {
+            Some(_) => self.next.get_edge_gradient(),
+            None => self.prev.get_edge_gradient(),
+        }
+    }
+
+    fn get_arrow_start(&self) -> &Option<Arrow> {
+        match self.next.get_arrow_start() {
+            Some(_) => self.next.get_arrow_start(),
+            None => self.prev.get_arrow_start(),
+        }
+    }
+
+    fn get_arrow_end(&self) -> &Option<Arrow> {
+        match self.next.get_arrow_end() {
+            Some(_) => self.next.get_arrow_end(),
+            None => self.prev.get_arrow_end(),
+        }
+    }
+
     fn get_line_width(&self) -> &Option<f32> {
         match self.next.get_line_width() {
             Some(_) => self.next.get_line_width(),
@@ -152,6 +253,13 @@ impl PathOpt for Stack<'_> {
         }
     }
 
+    fn get_miter_limit(&self) -> &Option<f32> {
+        match self.next.get_miter_limit() {
+            Some(_) => self.next.get_miter_limit(),
+            None => self.prev.get_miter_limit(),
+        }
+    }
+
     fn get_cap_style(&self) -> &Option<CapStyle> {
         match self.next.get_cap_style() {
             Some(_) => self.next.get_cap_style(),
@@ -179,6 +287,34 @@ impl PathOpt for Stack<'_> {
             None => self.prev.get_hatch(),
         }
     }
+
+    fn get_hatch_color(&self) -> &Option<Color> {
+        match self.next.get_hatch_color() {
+            Some(_) => self.next.get_hatch_color(),
+            None => self.prev.get_hatch_color(),
+        }
+    }
+
+    fn get_hatch_line_width(&self) -> &Option<f32> {
+        match self.next.get_hatch_line_width() {
+            Some(_) => self.next.get_hatch_line_width(),
+            None => self.prev.get_hatch_line_width(),
+        }
+    }
+
+    fn get_antialias(&self) -> &Option<bool> {
+        match self.next.get_antialias() {
+            Some(_) => self.next.get_antialias(),
+            None => self.prev.get_antialias(),
+        }
+    }
+
+    fn get_filter(&self) -> &Option<Filter> {
+        match self.next.get_filter() {
+            Some(_) => self.next.get_filter(),
+            None => self.prev.get_filter(),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -188,15 +324,37 @@ pub enum LineStyle {
     Dashed,
     DashDot,
     Dot,
+    /// Alternating on and off lengths in multiples of the line width. 
+    /// An odd-length pattern is repeated, like SVG stroke-dasharray.
+    /// An empty, zero-sum or negative pattern draws a solid line.
     OnOff(Vec<f32>),
 }
 
 impl LineStyle {
+    ///
+    /// Returns the on/off dash lengths for a line width. An empty pattern
+    /// is a solid line.
+    /// 
     pub fn to_pattern(&self, lw: f32) -> Vec<f32> {
         match self {
             Self::Dot => { vec![lw, 2. * lw] }
             Self::Dashed => { vec![4. * lw, 2. * lw] }
             Self::DashDot => { vec![4. * lw, 2. * lw, lw, 2. * lw] }
+            Self::OnOff(pattern) => {
+                // like SVG, an invalid or zero-sum pattern is solid
+                if pattern.iter().any(|v| ! v.is_finite() || *v < 0.)
+                    || pattern.iter().sum::<f32>() <= 0. {
+                    return Vec::new();
+                }
+
+                let mut pattern: Vec<f32> = pattern.iter().map(|v| v * lw).collect();
+
+                if pattern.len() % 2 == 1 {
+                    pattern.extend_from_within(..);
+                }
+
+                pattern
+            }
             _ => panic!("Unexpected linestyle {:?}", self)
         }
     }
@@ -284,8 +442,19 @@ impl FromStr for CapStyle {
         }
     }
 }
-#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
-pub enum Hatch {
-    Vertical,
-    Horizontal,
-}
\ No newline at end of file
+#[cfg(test)]
+mod test {
+    use super::LineStyle;
+
+    #[test]
+    fn to_pattern() {
+        assert_eq!(LineStyle::Dashed.to_pattern(2.), vec![8., 4.]);
+        assert_eq!(LineStyle::OnOff(vec![3., 1.]).to_pattern(2.), vec![6., 2.]);
+        assert_eq!(LineStyle::OnOff(vec![3., 1., 2.]).to_pattern(1.), vec![3., 1., 2., 3., 1., 2.]);
+
+        assert_eq!(LineStyle::OnOff(vec![]).to_pattern(2.), Vec::<f32>::new());
+        assert_eq!(LineStyle::OnOff(vec![0., 0.]).to_pattern(2.), Vec::<f32>::new());
+        assert_eq!(LineStyle::OnOff(vec![3., -1.]).to_pattern(2.), Vec::<f32>::new());
+        assert_eq!(LineStyle::OnOff(vec![3., f32::NAN]).to_pattern(2.), Vec::<f32>::new());
+    }
+}
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use crate::{CapStyle, Color, Hatch, JoinStyle, LineStyle, PathOpt, TextureId};
+use crate::{Arrow, CapStyle, Color, Filter, Gradient, Hatch, JoinStyle, LineStyle, PathOpt, TextureId};
 
 #[derive(Clone)]
 pub struct PathStyleBase {
@@ -8,16 +8,28 @@ pub struct PathStyleBase {
     face_color: Option<Color>,
     edge_color: Option<Color>,
 
+    face_gradient: Option<Gradient>,
+    edge_gradient: Option<Gradient>,
+
     line_width: Option<f32>,
     join_style: Option<JoinStyle>,
+    miter_limit: Option<f32>,
     cap_style: Option<CapStyle>,
 
     line_style: Option<LineStyle>,
+    dash_offset: Option<f32>,
     alpha: Option<f32>,
     texture: Option<TextureId>,
     hatch: Option<Hatch>,
+    hatch_color: Option<Color>,
+    hatch_line_width: Option<f32>,
+    antialias: Option<bool>,
+    filter: Option<Filter>,
 
     gap_color: Option<Color>,
+
+    arrow_start: Option<Arrow>,
+    arrow_end: Option<Arrow>,
 }
 
 impl PathStyleBase {
@@ -44,12 +56,48 @@ impl PathStyleBase {
         self
     }
 
+    pub fn face_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.face_gradient = Some(gradient);
+
+        self
+    }
+
+    pub fn edge_gradient(&mut self, gradient: Gradient) -> &mut Self {
+        self.edge_gradient = Some(gradient);
+
+        self
+    }
+
     pub fn line_style(&mut self, line_style: impl Into<LineStyle>) -> &mut Self {
         self.line_style = Some(line_style.into());
 
         self
     }
 
+    pub fn dash_offset(&mut self, offset: f32) -> &mut Self {
+        self.dash_offset = Some(offset);
+
+        self
+    }
+
+    pub fn gap_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.gap_color = Some(color.into());
+
+        self
+    }
+
+    pub fn arrow_start(&mut self, arrow: impl Into<Arrow>) -> &mut Self {
+        self.arrow_start = Some(arrow.into());
+
+        self
+    }
+
+    pub fn arrow_end(&mut self, arrow: impl Into<Arrow>) -> &mut Self {
+        self.arrow_end = Some(arrow.into());
+
+        self
+    }
+
     pub fn line_width(&mut self, linewidth: f32) -> &mut Self {
         // assert!(linewidth > 0.);
 
@@ -64,6 +112,18 @@ impl PathStyleBase {
         self
     }
 
+    ///
+    /// Miter limit as a ratio of the line width. SVG semantics: values less
+    /// than 1 are invalid.
+    ///
+    pub fn miter_limit(&mut self, miter_limit: f32) -> &mut Self {
+        assert!(miter_limit >= 1., "miter_limit must be at least 1 {}", miter_limit);
+
+        self.miter_limit = Some(miter_limit);
+
+        self
+    }
+
     pub fn cap_style(&mut self, capstyle: impl Into<CapStyle>) -> &mut Self {
         self.cap_style = Some(capstyle.into());
 
@@ -87,6 +147,32 @@ impl PathStyleBase {
 
         self
     }
+
+    pub fn hatch_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.hatch_color = Some(color.into());
+
+        self
+    }
+
+    pub fn hatch_line_width(&mut self, linewidth: f32) -> &mut Self {
+        assert!(linewidth > 0., "hatch line width must be positive {}", linewidth);
+
+        self.hatch_line_width = Some(linewidth);
+
+        self
+    }
+
+    pub fn antialias(&mut self, is_antialias: bool) -> &mut Self {
+        self.antialias = Some(is_antialias);
+
+        self
+    }
+
+    pub fn filter(&mut self, filter: Filter) -> &mut Self {
+        self.filter = Some(filter);
+
+        self
+    }
 }
 
 impl fmt::Debug for PathStyleBase {
@@ -104,6 +190,14 @@ impl fmt::Debug for PathStyleBase {
         if let Some(edge_color) = &self.edge_color {
             fmt.field("edge_color", edge_color);
         }
+
+        if let Some(face_gradient) = &self.face_gradient {
+            fmt.field("face_gradient", face_gradient);
+        }
+
+        if let Some(edge_gradient) = &self.edge_gradient {
+            fmt.field("edge_gradient", edge_gradient);
+        }
         
         if let Some(line_width) = &self.line_width {
             fmt.field("line_width", line_width);
@@ -113,6 +207,10 @@ impl fmt::Debug for PathStyleBase {
             fmt.field("join_style", join_style);
         }
 
+        if let Some(miter_limit) = &self.miter_limit {
+            fmt.field("miter_limit", miter_limit);
+        }
+
         if let Some(cap_style) = &self.cap_style {
             fmt.field("cap_style", cap_style);
         }
@@ -121,14 +219,34 @@ impl fmt::Debug for PathStyleBase {
             fmt.field("line_style", line_style);
         }
         
+        if let Some(dash_offset) = &self.dash_offset {
+            fmt.field("dash_offset", dash_offset);
+        }
+        
         if let Some(alpha) = &self.alpha {
             fmt.field("alpha", alpha);
         }
         
+        if let Some(antialias) = &self.antialias {
+            fmt.field("antialias", antialias);
+        }
+
+        if let Some(filter) = &self.filter {
+            fmt.field("filter", filter);
+        }
+        
         if let Some(gap_color) = &self.gap_color {
             fmt.field("gap_color", gap_color);
         }
         
+        if let Some(arrow_start) = &self.arrow_start {
+            fmt.field("arrow_start", arrow_start);
+        }
+        
+        if let Some(arrow_end) = &self.arrow_end {
+            fmt.field("arrow_end", arrow_end);
+        }
+        
         fmt.finish()
     }
 }
@@ -148,6 +266,14 @@ impl PathOpt for PathStyleBase {
         }
     }
 
+    fn get_face_gradient(&self) -> &Option<Gradient> {
+        &self.face_gradient
+    }
+
+    fn get_edge_gradient(&self) -> &Option<Gradient> {
+        &self.edge_gradient
+    }
+
     fn get_line_width(&self) -> &Option<f32> {
         &self.line_width
     }
@@ -156,6 +282,10 @@ impl PathOpt for PathStyleBase {
         &self.join_style
     }
 
+    fn get_miter_limit(&self) -> &Option<f32> {
+        &self.miter_limit
+    }
+
     fn get_cap_style(&self) -> &Option<CapStyle> {
         &self.cap_style
     }
@@ -164,6 +294,22 @@ impl PathOpt for PathStyleBase {
         &self.line_style
     }
 
+    fn get_dash_offset(&self) -> &Option<f32> {
+        &self.dash_offset
+    }
+
+    fn get_gap_color(&self) -> &Option<Color> {
+        &self.gap_color
+    }
+
+    fn get_arrow_start(&self) -> &Option<Arrow> {
+        &self.arrow_start
+    }
+
+    fn get_arrow_end(&self) -> &Option<Arrow> {
+        &self.arrow_end
+    }
+
     fn get_alpha(&self) -> &Option<f32> {
         &self.alpha
     }
@@ -175,6 +321,22 @@ impl PathOpt for PathStyleBase {
     fn get_hatch(&self) -> &Option<Hatch> {
         &self.hatch
     }
+
+    fn get_hatch_color(&self) -> &Option<Color> {
+        &self.hatch_color
+    }
+
+    fn get_hatch_line_width(&self) -> &Option<f32> {
+        &self.hatch_line_width
+    }
+
+    fn get_antialias(&self) -> &Option<bool> {
+        &self.antialias
+    }
+
+    fn get_filter(&self) -> &Option<Filter> {
+        &self.filter
+    }
 }
 
 impl Default for PathStyleBase {
@@ -183,14 +345,24 @@ impl Default for PathStyleBase {
             color: None,
             face_color: None,
             edge_color: None,
+            face_gradient: None,
+            edge_gradient: None,
             line_width: None,
             join_style: None,
+            miter_limit: None,
             cap_style: None,
             line_style: None,
+            dash_offset: None,
             gap_color: None,
+            arrow_start: None,
+            arrow_end: None,
             alpha: None,
             texture: None,
             hatch: None,
+            hatch_color: None,
+            hatch_line_width: None,
+            antialias: None,
+            filter: None,
         }
     }
 }
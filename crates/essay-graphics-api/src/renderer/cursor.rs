@@ -0,0 +1,24 @@
+///
+/// Mouse cursor shape a drawable can request for the current hover
+/// position, such as resize arrows over a splitter or a pointer over a
+/// clickable legend entry.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    #[default]
+    Default,
+    /// Clickable item, usually a hand
+    Pointer,
+    /// Text selection, usually an I-beam
+    Text,
+    Crosshair,
+    Move,
+    Grab,
+    Grabbing,
+    /// Left-right resize, as for a vertical splitter
+    ResizeHorizontal,
+    /// Up-down resize, as for a horizontal splitter
+    ResizeVertical,
+    NotAllowed,
+    Wait,
+}
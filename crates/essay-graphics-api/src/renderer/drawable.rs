@@ -18,8 +18,12 @@ pub trait Drawable {
     ///
     /// Called to inform the drawable when an event occurs in the drawable.
     /// 
+    /// Returns true if the drawable handled the event, which stops the
+    /// event from propagating to drawables beneath it.
+    /// 
     #[allow(unused_variables)]
-    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        false
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::{renderer::Canvas, Bounds, Point};
+use crate::{renderer::{Canvas, KeyEvent, Modifiers}, Bounds, Point};
 
 // TODO: Consider changing these to abstract events like Pan, Zoom because
 // of tablets, etc.
@@ -6,23 +6,58 @@ use crate::{renderer::Canvas, Bounds, Point};
 pub enum Event {
     Resize(Bounds<Canvas>),
 
-    MouseLeftPress(Point),
-    MouseLeftRelease(Point),
+    MouseLeftPress(Point, Modifiers),
+    MouseLeftRelease(Point, Modifiers),
+    /// Left drag from the press point to the cursor
+    MouseLeftDrag(Point, Point, Modifiers),
     Pan(Point, Point, Point),
     ResetView(Point),
 
-    MouseRightPress(Point),
-    MouseRightRelease(Point),
-    MouseRightDrag(Point, Point),
+    MouseRightPress(Point, Modifiers),
+    MouseRightRelease(Point, Modifiers),
+    MouseRightDrag(Point, Point, Modifiers),
     ZoomBounds(Point, Point),
     MouseRightDoubleClick(Point),
 
-    MouseMiddlePress(Point),
-    MouseMiddleRelease(Point),
-    MouseMiddleDrag(Point, Point),
+    MouseMiddlePress(Point, Modifiers),
+    MouseMiddleRelease(Point, Modifiers),
+    MouseMiddleDrag(Point, Point, Modifiers),
     MouseMiddleDoubleClick(Point),
 
+    /// Press of a button other than left, right or middle
+    MousePress(Point, MouseButton, Modifiers),
+    /// Release of a button other than left, right or middle
+    MouseRelease(Point, MouseButton, Modifiers),
+    /// Drag of a button other than left, right or middle, from the press
+    /// point to the cursor
+    MouseDrag(Point, Point, MouseButton, Modifiers),
+
+    /// Cursor move, whether or not a button is pressed
+    MouseMove(Point),
+    /// Cursor entered the view
+    Enter(Point),
+    /// Cursor left the view, where the point is outside the view
+    Leave(Point),
+
+    /// Mouse wheel or trackpad scroll at the cursor
+    MouseWheel(Point, ScrollDelta),
+    /// Trackpad pinch at the cursor, where a positive delta zooms in and 0.1
+    /// is a 10% magnification
+    Pinch(Point, f32),
+    /// Trackpad or two-finger rotation around a point, in radians where
+    /// positive is counterclockwise
+    Rotate(Point, f32),
+
+    /// Text input from a key press, at the cursor
     KeyPress(Point, char),
+    /// Key press, release or repeat at the cursor, including named keys
+    /// and modifiers
+    Key(Point, KeyEvent),
+
+    /// The view now receives key events
+    FocusGained,
+    /// The view no longer receives key events
+    FocusLost,
 }
 
 impl Event {
@@ -31,23 +66,40 @@ impl Event {
         match self {
             Event::Resize(_) => Point(0., 0.),
 
-            Event::MouseLeftPress(point) => *point,
-            Event::MouseLeftRelease(point) => *point,
+            Event::MouseLeftPress(point, _) => *point,
+            Event::MouseLeftRelease(point, _) => *point,
+            Event::MouseLeftDrag(point, _, _) => *point,
             Event::Pan(point, _, _) => *point,
             Event::ResetView(point) => *point,
 
-            Event::MouseRightPress(point) => *point,
-            Event::MouseRightRelease(point) => *point,
-            Event::MouseRightDrag(point, _) => *point,
+            Event::MouseRightPress(point, _) => *point,
+            Event::MouseRightRelease(point, _) => *point,
+            Event::MouseRightDrag(point, _, _) => *point,
             Event::ZoomBounds(point, _) => *point,
             Event::MouseRightDoubleClick(point) => *point,
 
-            Event::MouseMiddlePress(point) => *point,
-            Event::MouseMiddleRelease(point) => *point,
-            Event::MouseMiddleDrag(point, _) => *point,
+            Event::MouseMiddlePress(point, _) => *point,
+            Event::MouseMiddleRelease(point, _) => *point,
+            Event::MouseMiddleDrag(point, _, _) => *point,
             Event::MouseMiddleDoubleClick(point) => *point,
 
+            Event::MousePress(point, _, _) => *point,
+            Event::MouseRelease(point, _, _) => *point,
+            Event::MouseDrag(point, _, _, _) => *point,
+
+            Event::MouseMove(point) => *point,
+            Event::Enter(point) => *point,
+            Event::Leave(point) => *point,
+
+            Event::MouseWheel(point, _) => *point,
+            Event::Pinch(point, _) => *point,
+            Event::Rotate(point, _) => *point,
+
             Event::KeyPress(point, _) => *point,
+            Event::Key(point, _) => *point,
+
+            Event::FocusGained => Point(0., 0.),
+            Event::FocusLost => Point(0., 0.),
         }
     }
 
@@ -56,23 +108,75 @@ impl Event {
         match self {
             Event::Resize(_) => true,
 
-            Event::MouseLeftPress(point) => bounds.contains(point),
-            Event::MouseLeftRelease(point) => bounds.contains(point),
+            Event::MouseLeftPress(point, _) => bounds.contains(point),
+            Event::MouseLeftRelease(point, _) => bounds.contains(point),
+            Event::MouseLeftDrag(point, _, _) => bounds.contains(point),
             Event::Pan(point, _, _) => bounds.contains(point),
             Event::ResetView(point) => bounds.contains(point),
 
-            Event::MouseRightPress(point) => bounds.contains(point),
-            Event::MouseRightRelease(point) => bounds.contains(point),
-            Event::MouseRightDrag(point, _) => bounds.contains(point),
+            Event::MouseRightPress(point, _) => bounds.contains(point),
+            Event::MouseRightRelease(point, _) => bounds.contains(point),
+            Event::MouseRightDrag(point, _, _) => bounds.contains(point),
             Event::ZoomBounds(point, _) => bounds.contains(point),
             Event::MouseRightDoubleClick(point) => bounds.contains(point),
 
-            Event::MouseMiddlePress(point) => bounds.contains(point),
-            Event::MouseMiddleRelease(point) => bounds.contains(point),
-            Event::MouseMiddleDrag(point, _) => bounds.contains(point),
+            Event::MouseMiddlePress(point, _) => bounds.contains(point),
+            Event::MouseMiddleRelease(point, _) => bounds.contains(point),
+            Event::MouseMiddleDrag(point, _, _) => bounds.contains(point),
             Event::MouseMiddleDoubleClick(point) => bounds.contains(point),
 
+            Event::MousePress(point, _, _) => bounds.contains(point),
+            Event::MouseRelease(point, _, _) => bounds.contains(point),
+            Event::MouseDrag(point, _, _, _) => bounds.contains(point),
+
+            Event::MouseMove(point) => bounds.contains(point),
+            Event::Enter(point) => bounds.contains(point),
+            Event::Leave(_) => true,
+
+            Event::MouseWheel(point, _) => bounds.contains(point),
+            Event::Pinch(point, _) => bounds.contains(point),
+            Event::Rotate(point, _) => bounds.contains(point),
+
             Event::KeyPress(_, _) => true,
+            Event::Key(_, _) => true,
+
+            Event::FocusGained => true,
+            Event::FocusLost => true,
         }
     }
-}
\ No newline at end of file
+}
+
+///
+/// Mouse button, including the back and forward side buttons.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Back,
+    Forward,
+    Other(u16),
+}
+
+///
+/// Scroll amount from a mouse wheel in lines, or from a trackpad in pixels.
+/// Positive y is a scroll up, away from the user.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollDelta {
+    Line(f32, f32),
+    Pixel(f32, f32),
+}
+
+impl ScrollDelta {
+    ///
+    /// Scroll amount in pixels, where line_height is the pixels in a line.
+    ///
+    pub fn to_pixels(&self, line_height: f32) -> Point {
+        match self {
+            ScrollDelta::Line(x, y) => Point(x * line_height, y * line_height),
+            ScrollDelta::Pixel(x, y) => Point(*x, *y),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use crate::{path_opt::StyleErr, Filter};
+
+///
+/// Compositing options for a group of draws. A group is rendered offscreen
+/// and then blended into its parent as a single image, so overlapping
+/// primitives inside the group don't accumulate alpha.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Group {
+    opacity: f32,
+    blend: BlendMode,
+    filter: Option<Filter>,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self {
+            opacity: 1.,
+            blend: BlendMode::Normal,
+            filter: None,
+        }
+    }
+
+    pub fn opacity(&mut self, opacity: f32) -> &mut Self {
+        assert!(0. <= opacity && opacity <= 1., "opacity must be in [0, 1] {}", opacity);
+
+        self.opacity = opacity;
+
+        self
+    }
+
+    pub fn blend(&mut self, blend: impl Into<BlendMode>) -> &mut Self {
+        self.blend = blend.into();
+
+        self
+    }
+
+    ///
+    /// Filter applied to the group's image before it's composited.
+    ///
+    pub fn filter(&mut self, filter: Filter) -> &mut Self {
+        self.filter = Some(filter);
+
+        self
+    }
+
+    #[inline]
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    #[inline]
+    pub fn get_blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    #[inline]
+    pub fn get_filter(&self) -> Option<Filter> {
+        self.filter
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Group::new()
+    }
+}
+
+///
+/// Blend mode for compositing a group into its parent.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlendMode {
+    /// Source over destination
+    Normal,
+    /// Darkens by multiplying the source and destination colors
+    Multiply,
+    /// Lightens by multiplying the inverted colors
+    Screen,
+    /// Adds the source to the destination
+    Additive,
+}
+
+impl FromStr for BlendMode {
+    type Err = StyleErr;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "normal" => Ok(Self::Normal),
+            "multiply" => Ok(Self::Multiply),
+            "screen" => Ok(Self::Screen),
+            "additive" => Ok(Self::Additive),
+            "add" => Ok(Self::Additive),
+            _ => Err(StyleErr(format!("'{}' is an unknown blend mode", name)))
+        }
+    }
+}
+
+impl From<&str> for BlendMode {
+    fn from(name: &str) -> Self {
+        match Self::from_str(name) {
+            Ok(blend) => blend,
+            Err(err) => panic!("{}", err.0),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+///
+/// Logical key, after the keyboard layout is applied. Character keys hold
+/// the character, such as 'a' or 'A' with shift.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+
+    Enter,
+    Tab,
+    Space,
+    Backspace,
+    Delete,
+    Escape,
+    Insert,
+
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+
+    Home,
+    End,
+    PageUp,
+    PageDown,
+
+    /// Function key F1 to F24
+    F(u8),
+
+    Shift,
+    Control,
+    Alt,
+    Super,
+
+    /// Keys without a logical name here
+    Other,
+}
+
+///
+/// Whether a key was pressed, released, or repeated while held down.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyState {
+    Press,
+    Release,
+    Repeat,
+}
+
+///
+/// Modifier keys held during an input event. super_key is the Command key
+/// on macOS and the Windows key on Windows.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        super_key: false,
+    };
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+///
+/// Key press, release or repeat with the modifiers at the time.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    key: Key,
+    state: KeyState,
+    modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub fn new(key: Key, state: KeyState, modifiers: Modifiers) -> Self {
+        Self {
+            key,
+            state,
+            modifiers,
+        }
+    }
+
+    #[inline]
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    #[inline]
+    pub fn state(&self) -> KeyState {
+        self.state
+    }
+
+    #[inline]
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    ///
+    /// True for a press or a repeat.
+    ///
+    #[inline]
+    pub fn is_pressed(&self) -> bool {
+        self.state != KeyState::Release
+    }
+}
@@ -0,0 +1,28 @@
+///
+/// Drawing layer (z-order) for a view. Content in a higher layer is drawn
+/// above content in a lower layer, regardless of the order of the draw calls.
+/// Draws within the same layer keep their call order.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Layer(pub i32);
+
+impl Layer {
+    /// Below the data, such as grid lines and backgrounds
+    pub const BACKGROUND: Layer = Layer(-1000);
+
+    pub const DEFAULT: Layer = Layer(0);
+
+    /// Above the data, such as a cursor crosshair
+    pub const OVERLAY: Layer = Layer(1000);
+
+    #[inline]
+    pub fn index(&self) -> i32 {
+        self.0
+    }
+}
+
+impl From<i32> for Layer {
+    fn from(value: i32) -> Self {
+        Layer(value)
+    }
+}
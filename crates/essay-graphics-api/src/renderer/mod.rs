@@ -1,8 +1,12 @@
 mod backend;
 mod canvas;
+mod cursor;
 mod drawable;
 mod renderer;
 mod event;
+mod group;
+mod key;
+mod layer;
 
 pub use backend::{
     Backend, DeviceErr,
@@ -10,9 +14,17 @@ pub use backend::{
 
 pub use canvas::Canvas;
 
+pub use cursor::Cursor;
+
 pub use drawable::Drawable;
 
-pub use event::Event;
+pub use event::{Event, MouseButton, ScrollDelta};
+
+pub use group::{BlendMode, Group};
+
+pub use key::{Key, KeyEvent, KeyState, Modifiers};
+
+pub use layer::Layer;
 
 pub use renderer::{
     Renderer, Result, RenderErr,
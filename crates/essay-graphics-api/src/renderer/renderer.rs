@@ -1,10 +1,10 @@
 use essay_tensor::Tensor;
 
 use crate::{
-    form::{Form, FormId, Matrix4, Shape, ShapeId}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, TextStyle, TextureId
+    form::{Form, FormId, Matrix4, Shape, ShapeId}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, ScalarMap, TextStyle, TextureId
 };
 
-use super::{Canvas, Drawable};
+use super::{Canvas, Cursor, Drawable, Group, Layer};
 
 pub trait Renderer {
     ///
@@ -65,6 +65,20 @@ pub trait Renderer {
         colors: &Tensor<u8>,  // [rows, cols, 4]
     ) -> Result<()>;
 
+    ///
+    /// Draws a scalar field, mapping each value to a color with the map's
+    /// range and lookup table. Returns `RenderErr::InvalidShape` when the
+    /// values aren't a non-empty [rows, cols] tensor, and
+    /// `RenderErr::TooLarge` when the field or lookup table exceeds the
+    /// backend's texture size.
+    ///
+    fn draw_scalar_image(
+        &mut self,
+        bounds: &Bounds<Canvas>,
+        values: &Tensor<f32>,  // [rows, cols]
+        map: &ScalarMap,
+    ) -> Result<()>;
+
     fn create_image(
         &mut self,
         colors: &Tensor<u8>, // [rows, cols, 4]
@@ -119,10 +133,54 @@ pub trait Renderer {
         &mut dyn Drawable
     ) -> Result<()>;
 
+    ///
+    /// Draws a view in the given layer. Higher layers are drawn above lower
+    /// layers, independent of call order. Nested draw_with calls inherit
+    /// the layer.
+    ///
+    fn draw_with_layer(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        layer: Layer,
+        drawable: &mut dyn Drawable
+    ) -> Result<()>;
+
+    ///
+    /// Draws a view as a group, which is rendered offscreen and then
+    /// composited with the group's opacity and blend mode.
+    ///
+    fn draw_group(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        group: &Group,
+        drawable: &mut dyn Drawable
+    ) -> Result<()>;
+
+    ///
+    /// Draws a view into a new offscreen image covering pos, which can be
+    /// drawn in later frames with draw_image_ref. Caching a static layer
+    /// avoids redrawing it when only an overlay changes.
+    ///
+    fn draw_to_image(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        drawable: &mut dyn Drawable
+    ) -> Result<ImageId>;
+
     fn request_redraw(
         &mut self,
         bounds: &Bounds<Canvas>
     );
+
+    ///
+    /// Requests the cursor shape for the current hover position, usually
+    /// from a MouseMove or Enter event. The cursor resets to the default
+    /// on each cursor move, and an active pan or zoom gesture overrides it.
+    ///
+    fn set_cursor(
+        &mut self,
+        cursor: Cursor
+    );
 }
 
 pub type Result<T, E=RenderErr> = std::result::Result<T, E>;
@@ -130,4 +188,6 @@ pub type Result<T, E=RenderErr> = std::result::Result<T, E>;
 #[derive(Debug)]
 pub enum RenderErr {
     NotImplemented,
+    InvalidShape,
+    TooLarge,
 }
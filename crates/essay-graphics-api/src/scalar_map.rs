@@ -0,0 +1,154 @@
+use crate::Color;
+
+///
+/// Maps scalar values to colors for a scalar-field image. Values in
+/// [vmin, vmax] select one of the lookup table's colors, which are evenly
+/// spaced over the range.
+///
+/// NaN values use the nan color, and values outside the range use the over
+/// and under colors, which default to the table's end colors.
+///
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScalarMap {
+    colors: Vec<Color>,
+    vmin: f32,
+    vmax: f32,
+
+    nan_color: Color,
+    over_color: Option<Color>,
+    under_color: Option<Color>,
+}
+
+impl ScalarMap {
+    pub fn new(colors: impl Into<Vec<Color>>) -> Self {
+        let colors = colors.into();
+
+        assert!(colors.len() > 0, "scalar map needs at least one color");
+
+        Self {
+            colors,
+            vmin: 0.,
+            vmax: 1.,
+
+            nan_color: Color::none(),
+            over_color: None,
+            under_color: None,
+        }
+    }
+
+    pub fn range(&mut self, vmin: f32, vmax: f32) -> &mut Self {
+        assert!(vmin <= vmax, "vmin must not be larger than vmax ({}, {})", vmin, vmax);
+
+        self.vmin = vmin;
+        self.vmax = vmax;
+
+        self
+    }
+
+    pub fn nan_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.nan_color = color.into();
+
+        self
+    }
+
+    pub fn over_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.over_color = Some(color.into());
+
+        self
+    }
+
+    pub fn under_color(&mut self, color: impl Into<Color>) -> &mut Self {
+        self.under_color = Some(color.into());
+
+        self
+    }
+
+    #[inline]
+    pub fn get_colors(&self) -> &Vec<Color> {
+        &self.colors
+    }
+
+    #[inline]
+    pub fn get_vmin(&self) -> f32 {
+        self.vmin
+    }
+
+    #[inline]
+    pub fn get_vmax(&self) -> f32 {
+        self.vmax
+    }
+
+    #[inline]
+    pub fn get_nan_color(&self) -> Color {
+        self.nan_color
+    }
+
+    pub fn get_over_color(&self) -> Color {
+        match self.over_color {
+            Some(color) => color,
+            None => self.colors[self.colors.len() - 1],
+        }
+    }
+
+    pub fn get_under_color(&self) -> Color {
+        match self.under_color {
+            Some(color) => color,
+            None => self.colors[0],
+        }
+    }
+
+    ///
+    /// Value scaled so the range is [0, 1]. A zero-width range maps to 0.
+    ///
+    pub fn normalize(&self, value: f32) -> f32 {
+        let width = self.vmax - self.vmin;
+
+        if width > 0. {
+            (value - self.vmin) / width
+        } else {
+            0.
+        }
+    }
+
+    ///
+    /// Color for a value, matching the renderer's lookup.
+    ///
+    pub fn color(&self, value: f32) -> Color {
+        if value.is_nan() {
+            return self.nan_color;
+        }
+
+        let t = self.normalize(value);
+
+        if t < 0. {
+            self.get_under_color()
+        } else if t > 1. {
+            self.get_over_color()
+        } else {
+            let n = self.colors.len();
+
+            self.colors[((t * n as f32) as usize).min(n - 1)]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Color;
+
+    use super::ScalarMap;
+
+    #[test]
+    fn color() {
+        let mut map = ScalarMap::new(vec![Color(0x000000ff), Color(0x808080ff), Color(0xffffffff)]);
+        map.range(-1., 2.).nan_color(Color(0xff0000ff)).over_color(Color(0x00ff00ff));
+
+        assert_eq!(map.color(-1.), Color(0x000000ff));
+        assert_eq!(map.color(0.5), Color(0x808080ff));
+        assert_eq!(map.color(2.), Color(0xffffffff));
+
+        assert_eq!(map.color(-2.), Color(0x000000ff));
+        assert_eq!(map.color(3.), Color(0x00ff00ff));
+        assert_eq!(map.color(f32::NAN), Color(0xff0000ff));
+    }
+}
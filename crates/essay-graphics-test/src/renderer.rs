@@ -1,5 +1,7 @@
+use std::mem;
+
 use essay_graphics_api::{
-    form::{Form, FormId, Matrix4, Shape, ShapeId}, renderer::{Canvas, Drawable, RenderErr, Renderer, Result}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, TextStyle, TextureId
+    form::{Form, FormId, Matrix4, Shape, ShapeId}, renderer::{Canvas, Cursor, Drawable, Group, Layer, RenderErr, Renderer, Result}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, ScalarMap, TextStyle, TextureId
 };
 use essay_tensor::Tensor;
 
@@ -7,6 +9,10 @@ pub struct TestRenderer {
     bounds: Bounds<Canvas>,
     pos: Bounds<Canvas>,
     scale_factor: f32,
+    layer: Layer,
+    group: Option<Group>,
+    image_id: usize,
+    cursor: Cursor,
 
     vec: Vec<String>,
 }
@@ -19,6 +25,10 @@ impl TestRenderer {
             pos: bounds.clone(),
             bounds,
             scale_factor: 1.,
+            layer: Layer::DEFAULT,
+            group: None,
+            image_id: 0,
+            cursor: Cursor::Default,
             vec: Vec::new(),
         }
     }
@@ -37,6 +47,18 @@ impl TestRenderer {
         self
     }
 
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    pub fn group(&self) -> Option<Group> {
+        self.group
+    }
+
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
     pub fn drain(&mut self) -> Vec<String> {
         self.vec.drain(..).collect()
     }
@@ -109,6 +131,20 @@ impl Renderer for TestRenderer {
         todo!()
     }
 
+    fn draw_scalar_image(
+        &mut self,
+        bounds: &Bounds<Canvas>,
+        values: &Tensor<f32>,  // [rows, cols]
+        _map: &ScalarMap,
+    ) -> Result<(), RenderErr> {
+        self.vec.push(format!(
+            "draw_scalar_image({:?}, [{}, {}])",
+            bounds, values.dim(0), values.dim(1)
+        ));
+
+        Ok(())
+    }
+
     fn create_image(
         &mut self,
         _colors: &Tensor<u8>, // [rows, cols, 4]
@@ -179,15 +215,69 @@ impl Renderer for TestRenderer {
     ) {
         todo!()
     }
+
+    fn set_cursor(
+        &mut self,
+        cursor: Cursor
+    ) {
+        self.cursor = cursor;
+    }
     
-    fn draw_with(&mut self, _pos: &Bounds<Canvas>, _drawable: &mut dyn Drawable) -> Result<()> {
-        todo!()
+    fn draw_with(&mut self, pos: &Bounds<Canvas>, drawable: &mut dyn Drawable) -> Result<()> {
+        let layer = self.layer;
+
+        self.draw_with_layer(pos, layer, drawable)
+    }
+
+    fn draw_with_layer(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        layer: Layer,
+        drawable: &mut dyn Drawable
+    ) -> Result<()> {
+        let prev_pos = mem::replace(&mut self.pos, pos.clone());
+        let prev_layer = mem::replace(&mut self.layer, layer);
+
+        let result = drawable.draw(self);
+
+        self.pos = prev_pos;
+        self.layer = prev_layer;
+
+        result
+    }
+
+    fn draw_group(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        group: &Group,
+        drawable: &mut dyn Drawable
+    ) -> Result<()> {
+        let prev_group = mem::replace(&mut self.group, Some(*group));
+
+        let result = self.draw_with(pos, drawable);
+
+        self.group = prev_group;
+
+        result
+    }
+
+    fn draw_to_image(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        drawable: &mut dyn Drawable
+    ) -> Result<ImageId> {
+        self.draw_with(pos, drawable)?;
+
+        self.image_id += 1;
+
+        Ok(ImageId::new(self.image_id))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use essay_graphics_api::{renderer::{Canvas, Renderer}, Bounds};
+    use essay_graphics_api::{renderer::{BlendMode, Canvas, Group, Layer, Renderer, Result}, Bounds, Color, ScalarMap};
+    use essay_tensor::Tensor;
 
     use super::TestRenderer;
 
@@ -207,4 +297,50 @@ mod test {
 
         assert_eq!(test.drain(), &["flush"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn draw_scalar_image() {
+        let mut test = TestRenderer::new([100., 100.]);
+
+        let pos = Bounds::<Canvas>::from((10., 10., 20., 20.));
+        let values = Tensor::from(vec![0f32; 6]).reshape([2, 3]);
+        let map = ScalarMap::new([Color(0x000000ff), Color(0xffffffff)]);
+
+        test.draw_scalar_image(&pos, &values, &map).unwrap();
+
+        assert_eq!(test.drain(), &[format!("draw_scalar_image({:?}, [2, 3])", pos)]);
+    }
+
+    #[test]
+    fn draw_with_layer() {
+        let mut test = TestRenderer::new([100., 100.]);
+
+        let pos = Bounds::<Canvas>::from((10., 10., 20., 20.));
+
+        test.draw_with_layer(&pos, Layer::OVERLAY, &mut |r: &mut dyn Renderer| -> Result<()> {
+            assert_eq!(r.pos(), &Bounds::<Canvas>::from((10., 10., 20., 20.)));
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(test.pos(), &Bounds::<Canvas>::from([100., 100.]));
+        assert_eq!(test.layer(), Layer::DEFAULT);
+    }
+
+    #[test]
+    fn draw_group() {
+        let mut test = TestRenderer::new([100., 100.]);
+
+        let pos = Bounds::<Canvas>::from((10., 10., 20., 20.));
+        let mut group = Group::new();
+        group.opacity(0.5).blend("multiply");
+
+        test.draw_group(&pos, &group, &mut |r: &mut dyn Renderer| -> Result<()> {
+            assert_eq!(r.pos(), &Bounds::<Canvas>::from((10., 10., 20., 20.)));
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(group.get_opacity(), 0.5);
+        assert_eq!(group.get_blend(), BlendMode::Multiply);
+        assert_eq!(test.group(), None);
+    }
+}
@@ -2,6 +2,8 @@ use bytemuck_derive::{Pod, Zeroable};
 use essay_graphics_api::{Point, Color, Affine2d};
 use wgpu::util::DeviceExt;
 
+use super::gradient::{lut_bind_group_layout, PaintStyle};
+
 pub struct BezierRender {
     vertex_stride: usize,
     vertex_vec: Vec<BezierVertex>,
@@ -18,12 +20,15 @@ pub struct BezierRender {
     is_stale: bool,
 
     pipeline: wgpu::RenderPipeline,
+    // single-sample pipeline for non-antialiased paths with msaa
+    pipeline_alias: Option<wgpu::RenderPipeline>,
 }
 
 impl BezierRender {
     pub(crate) fn new(
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -43,6 +48,8 @@ impl BezierRender {
             affine_0: [0.0, 0.0, 0.0, 0.0], 
             affine_1: [0.0, 0.0, 0.0, 0.0], 
             color: [0.0, 0.0, 0.0, 0.0],
+            paint_0: [0.0, 0.0, 0.0, 0.0],
+            paint_1: [0.0, 0.0, 0.0, 0.0],
         });
 
         let style_buffer = device.create_buffer_init(
@@ -56,7 +63,14 @@ impl BezierRender {
         let pipeline = create_bezier_pipeline(
             device, 
             format,
+            sample_count,
         );
+
+        let pipeline_alias = if sample_count > 1 {
+            Some(create_bezier_pipeline(device, format, 1))
+        } else {
+            None
+        };
     
         Self {
             vertex_stride: std::mem::size_of::<BezierVertex>(),
@@ -73,6 +87,7 @@ impl BezierRender {
 
             shape_items: Vec::new(),
             pipeline,
+            pipeline_alias,
         }
     }
 
@@ -287,6 +302,19 @@ impl BezierRender {
         &mut self, 
         color: Color,
         affine: &Affine2d,
+    ) {
+        self.draw_style_paint(color, PaintStyle::solid(), affine);
+    }
+
+    ///
+    /// Draws the current shape with a gradient paint, where color is
+    /// multiplied with the gradient's color.
+    /// 
+    pub(crate) fn draw_style_paint(
+        &mut self, 
+        color: Color,
+        paint: PaintStyle,
+        affine: &Affine2d,
     ) {
         let end = self.vertex_offset;
 
@@ -301,39 +329,28 @@ impl BezierRender {
         let item = &mut self.shape_items[len - 1];
         item.v_end = end;
 
-        self.style_vec[self.style_offset] = BezierStyle::new(affine, color);
+        self.style_vec[self.style_offset] = BezierStyle::new(affine, color, paint);
         self.style_offset += 1;
 
         item.s_end = self.style_offset;
     }
 
-    pub fn flush(
+    pub(crate) fn item_count(&self) -> usize {
+        self.shape_items.len()
+    }
+
+    ///
+    /// Writes the vertex and style buffers before any draw_item calls
+    /// 
+    pub fn prepare(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
-        view: &wgpu::TextureView,
-        encoder: &mut wgpu::CommandEncoder,
-        scissor: Option<(u32, u32, u32, u32)>,
     ) {
         if self.shape_items.len() == 0 {
             return;
         }
 
-        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: None,
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: wgpu::StoreOp::Store,
-                }
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
-
         if self.is_stale {
             self.is_stale = false;
  
@@ -365,33 +382,41 @@ impl BezierRender {
             0,
             bytemuck::cast_slice(self.style_vec.as_slice())
         );
+    }
 
-        rpass.set_pipeline(&self.pipeline);
-
-        if let Some((x, y, w, h)) = scissor {
-            rpass.set_scissor_rect(x, y, w, h);
-        }
+    ///
+    /// Draws a single bezier item into an existing render pass. The item
+    /// index is the draw order, which is shared with the shape2d renderer.
+    /// 
+    pub fn draw_item<'a>(
+        &'a self, 
+        rpass: &mut wgpu::RenderPass<'a>,
+        index: usize,
+        is_alias: bool,
+    ) {
+        let item = &self.shape_items[index];
 
-        for item in self.shape_items.drain(..) {
-            if item.v_start < item.v_end && item.s_start < item.s_end {
-                let stride = self.vertex_stride;
-                rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
-                    (stride * item.v_start) as u64..(stride * item.v_end) as u64
-                ));
-
-                let stride = self.style_stride;
-                rpass.set_vertex_buffer(1, self.style_buffer.slice(
-                    (stride * item.s_start) as u64..(stride * item.s_end) as u64
-                ));
-
-                rpass.draw(
-                    0..(item.v_end - item.v_start) as u32,
-                    0..(item.s_end - item.s_start) as u32,
-                );
+        if item.v_start < item.v_end && item.s_start < item.s_end {
+            match &self.pipeline_alias {
+                Some(pipeline) if is_alias => rpass.set_pipeline(pipeline),
+                _ => rpass.set_pipeline(&self.pipeline),
             }
-        }
 
-        self.vertex_offset = 0;
+            let stride = self.vertex_stride;
+            rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
+                (stride * item.v_start) as u64..(stride * item.v_end) as u64
+            ));
+
+            let stride = self.style_stride;
+            rpass.set_vertex_buffer(1, self.style_buffer.slice(
+                (stride * item.s_start) as u64..(stride * item.s_end) as u64
+            ));
+
+            rpass.draw(
+                0..(item.v_end - item.v_start) as u32,
+                0..(item.s_end - item.s_start) as u32,
+            );
+        }
     }
 
     fn vertex(&mut self, x: f32, y: f32) {
@@ -491,14 +516,18 @@ pub struct BezierStyle {
     affine_0: [f32; 4],
     affine_1: [f32; 4],
     color: [f32; 4],
+    paint_0: [f32; 4],
+    paint_1: [f32; 4],
 }
 
 impl BezierStyle {
-    const ATTRS: [wgpu::VertexAttribute; 3] =
+    const ATTRS: [wgpu::VertexAttribute; 5] =
         wgpu::vertex_attr_array![
             2 => Float32x4, 
             3 => Float32x4,
-            4 => Float32x4
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Float32x4
         ];
 
     pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
@@ -514,10 +543,12 @@ impl BezierStyle {
             affine_0: [0., 0., 0., 0.],
             affine_1: [0., 0., 0., 0.],
             color: [0., 0., 0., 0.],
+            paint_0: [0., 0., 0., 0.],
+            paint_1: [0., 0., 0., 0.],
         }
     }
 
-    fn new(affine: &Affine2d, color: Color) -> Self {
+    fn new(affine: &Affine2d, color: Color, paint: PaintStyle) -> Self {
         let mat = affine.mat();
 
         Self {
@@ -529,6 +560,8 @@ impl BezierStyle {
                 Color::srgb_to_lrgb(color.blue()),
                 color.alpha(),
             ],
+            paint_0: paint.geom,
+            paint_1: paint.mode,
         }
     }
 }
@@ -536,8 +569,14 @@ impl BezierStyle {
 fn create_bezier_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::include_wgsl!("bezier.wgsl"));
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("bezier.wgsl"),
+        source: wgpu::ShaderSource::Wgsl(
+            concat!(include_str!("paint.wgsl"), include_str!("bezier.wgsl")).into()
+        ),
+    });
 
     let vertex_entry = "vs_bezier";
     let fragment_entry = "fs_bezier";
@@ -548,6 +587,7 @@ fn create_bezier_pipeline(
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: None,
         bind_group_layouts: &[
+            &lut_bind_group_layout(device),
         ],
         push_constant_ranges: &[],
     });
@@ -586,7 +626,10 @@ fn create_bezier_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
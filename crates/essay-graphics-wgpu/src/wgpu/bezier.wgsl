@@ -7,14 +7,21 @@ struct StyleInput {
     @location(2) a0: vec4<f32>,
     @location(3) a1: vec4<f32>,
     @location(4) color: vec4<f32>,
+    @location(5) paint_0: vec4<f32>,
+    @location(6) paint_1: vec4<f32>,
 }
 
 struct VertexOutput {
     @location(0) tex_coord: vec3<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) paint_pos: vec2<f32>,
+    @location(3) @interpolate(flat) paint_0: vec4<f32>,
+    @location(4) @interpolate(flat) paint_1: vec4<f32>,
     @builtin(position) pos: vec4<f32>,
 };
 
+// paint() and t_lut are in paint.wgsl
+
 fn unpack_color(color: u32) -> vec4<f32> {
     return vec4<f32>(
         f32((color >> 24u) & 0xffu),
@@ -39,6 +46,9 @@ fn vs_bezier(
     out.pos = vec4<f32>(x, y, 0.0, 1.0);
     out.tex_coord = model.tex_coord;
     out.color = style.color;
+    out.paint_pos = model.pos;
+    out.paint_0 = style.paint_0;
+    out.paint_1 = style.paint_1;
     return out;
 }
 
@@ -52,7 +62,7 @@ fn fs_bezier(
     let u_sq = u * u;
     //if u * u < 1. - v {
     if w <= u_sq && u_sq <= v {
-        return in.color; // vec4<f32>(0.0, 1.0, 1.1, 1.0); // in.color;
+        return paint(in.color, in.paint_pos, in.paint_0, in.paint_1);
     } else {
         return vec4<f32>(0.0, 0.0, 0.0, 0.0);
     }
@@ -66,7 +76,7 @@ fn fs_bezier_rev(
     let v = in.tex_coord[1];
     //if u * u < 1. - v {
     if v < u * u {
-        return in.color; // vec4<f32>(0.0, 1.0, 1.1, 1.0); // in.color;
+        return paint(in.color, in.paint_pos, in.paint_0, in.paint_1);
     } else {
         return vec4<f32>(0.0, 0.0, 0.0, 0.0);
     }
@@ -1,14 +1,16 @@
 use essay_graphics_api::{
     form::{Form, FormId, Matrix4, Shape, ShapeId}, 
-    renderer::{Canvas, Drawable, RenderErr, Result}, 
-    Affine2d, Bounds, CapStyle, Clip, Color, FontStyle, FontTypeId, HorizAlign, ImageId, JoinStyle, LineStyle, Path, PathCode, PathOpt, Point, TextStyle, TextureId, VertAlign
+    renderer::{Canvas, Cursor, Drawable, Group, Layer, RenderErr, Result}, 
+    Affine2d, Bounds, CapStyle, Clip, Color, FontStyle, FontTypeId, HorizAlign, ImageId, JoinStyle, LineStyle, Path, PathCode, PathOpt, PathStyleBase, Point, ScalarMap, TextStyle, TextureId, VertAlign
 };
+use std::{mem, ops::Range, sync::Arc};
+
 use essay_tensor::Tensor;
 
 use crate::PlotRenderer;
 
 use super::{
-    bezier::BezierRender, form3d::Form3dRender, image::ImageRender, shape2d::Shape2dRender, shape2d_tex2::Shape2dTex2Render, shape2d_texture::Shape2dTextureRender, text::TextRender, text_cache::FontId, texture_store::TextureCache, triangle2d::Triangle2dRenderer, triangulate::triangulate2
+    bezier::BezierRender, form3d::Form3dRender, gradient::{GradientLut, PaintStyle}, group::GroupRender, image::ImageRender, msaa::{color_target, MsaaTarget}, scalar_image::ScalarImageRender, shape2d::Shape2dRender, shape2d_tex2::Shape2dTex2Render, shape2d_texture::Shape2dTextureRender, text::TextRender, text_cache::FontId, texture_store::TextureCache, triangle2d::Triangle2dRenderer, triangulate::triangulate2
 };
 
 
@@ -16,7 +18,12 @@ pub struct PlotCanvas {
     bounds: Bounds<Canvas>,
     scale_factor: f32,
 
+    sample_count: u32,
+    pub(crate) msaa: Option<MsaaTarget>,
+    is_antialias: bool,
+
     pub(crate) image_render: ImageRender,
+    pub(crate) scalar_image_render: ScalarImageRender,
     pub(crate) triangle_render: Triangle2dRenderer,
 
     pub(crate) form3d_render: Form3dRender,
@@ -25,15 +32,25 @@ pub struct PlotCanvas {
     pub(crate) shape2d_render: Shape2dRender,
     pub(crate) shape2d_texture_render: Shape2dTextureRender,
     pub(crate) bezier_render: BezierRender,
+    pub(crate) gradient_lut: GradientLut,
     pub(crate) text_render: TextRender,
+    pub(crate) group_render: GroupRender,
 
     pub(crate) texture_store: TextureCache,
 
+    batches: Vec<DrawBatch>,
+    layer: Layer,
+    scissor: Option<(u32, u32, u32, u32)>,
+    group_depth: usize,
+    // layer of the outermost group, which all nested draws share
+    group_layer: Option<Layer>,
+
     font_id_default: FontId,
 
     to_gpu: Affine2d,
 
     is_request_redraw: bool,
+    cursor: Cursor,
 }
 
 impl PlotCanvas {
@@ -44,15 +61,41 @@ impl PlotCanvas {
         width: u32,
         height: u32,
     ) -> Self {
+        Self::with_sample_count(device, queue, format, width, height, 1)
+    }
+
+    ///
+    /// Creates a canvas with a multisample (MSAA) count for all pipelines.
+    /// A sample_count of 1 disables multisampling. 4 is supported by all
+    /// adapters.
+    ///
+    pub fn with_sample_count(
+        device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        assert!(sample_count > 0, "sample_count must be positive");
     
-        let image_render = ImageRender::new(device, format);
-        let triangle_render = Triangle2dRenderer::new(device, format);
-        let triangle3d_render = Form3dRender::new(device, format, width, height);
-        let shape2d_tex2_render = Shape2dTex2Render::new(device, format);
-        let shape2d_render = Shape2dRender::new(device, format);
-        let shape2d_texture_render = Shape2dTextureRender::new(device, queue, format);
-        let bezier_render = BezierRender::new(device, format);
-        let mut text_render = TextRender::new(device, format, 512, 512);
+        let image_render = ImageRender::new(device, format, sample_count);
+        let scalar_image_render = ScalarImageRender::new(device, format, sample_count);
+        let triangle_render = Triangle2dRenderer::new(device, format, sample_count);
+        let triangle3d_render = Form3dRender::new(device, format, width, height, sample_count);
+        let shape2d_tex2_render = Shape2dTex2Render::new(device, format, sample_count);
+        let shape2d_render = Shape2dRender::new(device, format, sample_count);
+        let shape2d_texture_render = Shape2dTextureRender::new(device, format, sample_count);
+        let bezier_render = BezierRender::new(device, format, sample_count);
+        let gradient_lut = GradientLut::new(device);
+        let mut text_render = TextRender::new(device, format, 512, 512, sample_count);
+        let group_render = GroupRender::new(device, format, width, height, sample_count);
+
+        let msaa = if sample_count > 1 {
+            Some(MsaaTarget::new(device, format, width, height, sample_count))
+        } else {
+            None
+        };
 
         let font_id_default = text_render.font("default");
 
@@ -62,7 +105,12 @@ impl PlotCanvas {
             bounds: Bounds::from([width as f32, height as f32]),
             scale_factor: 1.,
 
+            sample_count,
+            msaa,
+            is_antialias: true,
+
             image_render,
+            scalar_image_render,
             shape2d_render,
             shape2d_texture_render,
             text_render,
@@ -70,13 +118,22 @@ impl PlotCanvas {
             form3d_render: triangle3d_render,
             shape2d_tex2_render,
             bezier_render,
+            gradient_lut,
+            group_render,
 
             font_id_default,
             texture_store: TextureCache::new(),
 
+            batches: Vec::new(),
+            layer: Layer::DEFAULT,
+            scissor: None,
+            group_depth: 0,
+            group_layer: None,
+
             to_gpu: Affine2d::eye(),
 
             is_request_redraw: false,
+            cursor: Cursor::Default,
         }
     }
 
@@ -88,16 +145,352 @@ impl PlotCanvas {
         self.is_request_redraw = is_redraw;
     }
 
+    #[inline]
+    pub fn cursor(&self) -> Cursor {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: Cursor) {
+        self.cursor = cursor;
+    }
+
     pub fn clear(&mut self) {
         self.bezier_render.clear();
         self.text_render.clear();
         self.shape2d_render.clear();
         self.shape2d_texture_render.clear();
+        self.gradient_lut.clear();
         self.triangle_render.clear();
+        // group items hold image ids, which keep the images live
+        self.group_render.clear();
         self.image_render.clear();
+        self.scalar_image_render.clear();
 
         self.form3d_render.clear();
         self.shape2d_tex2_render.clear();
+
+        self.batches.clear();
+    }
+
+    ///
+    /// Draw batches in the order the draw calls were made, which the
+    /// renderer flushes in sequence to preserve painter's order.
+    ///
+    pub(crate) fn batches(&self) -> &Vec<DrawBatch> {
+        &self.batches
+    }
+
+    #[inline]
+    pub(crate) fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    ///
+    /// Sets the layer for following draws. The renderer sorts batches by layer
+    /// before flushing.
+    ///
+    pub(crate) fn set_layer(&mut self, layer: Layer) {
+        self.layer = layer;
+    }
+
+    #[inline]
+    pub(crate) fn scissor(&self) -> Option<(u32, u32, u32, u32)> {
+        self.scissor
+    }
+
+    ///
+    /// Sets the scissor clip for following draws, saved with each batch
+    /// because batches are flushed after the view is popped.
+    ///
+    pub(crate) fn set_scissor(&mut self, scissor: Option<(u32, u32, u32, u32)>) {
+        self.scissor = scissor;
+    }
+
+    ///
+    /// Starts a group, which the renderer draws into an offscreen target
+    /// until the matching end_group. Returns the group index for end_group.
+    ///
+    pub(crate) fn begin_group(&mut self, group: &Group) -> usize {
+        let index = self.group_render.push(group, self.group_depth, self.scale_factor);
+
+        self.begin_group_batch(index);
+
+        index
+    }
+
+    ///
+    /// Starts an image group, which is copied into a new image covering pos
+    /// instead of being composited. Returns the image and the group index
+    /// for end_group.
+    ///
+    pub(crate) fn begin_image(
+        &mut self, 
+        device: &wgpu::Device, 
+        pos: &Bounds<Canvas>
+    ) -> (ImageId, usize) {
+        let width = pos.width().round().max(1.) as u32;
+        let height = pos.height().round().max(1.) as u32;
+
+        let image = self.image_render.create_target(device, width, height);
+
+        // top-left corner in target pixels, like the scissor
+        let offset = [pos.xmin().round(), (self.bounds.ymax() - pos.ymax()).round()];
+
+        let index = self.group_render.push_image(
+            image.clone(), 
+            offset, 
+            height as f32, 
+            self.group_depth
+        );
+
+        self.begin_group_batch(index);
+
+        (image, index)
+    }
+
+    fn begin_group_batch(&mut self, index: usize) {
+        self.batch(DrawKind::GroupBegin, index);
+
+        if self.group_depth == 0 {
+            // a group's batches must stay together when sorting by layer
+            self.group_layer = Some(self.layer);
+        }
+
+        self.group_depth += 1;
+    }
+
+    #[inline]
+    pub(crate) fn is_group_open(&self) -> bool {
+        self.group_depth > 0
+    }
+
+    pub(crate) fn end_group(&mut self, index: usize) {
+        assert!(self.group_depth > 0, "end_group without begin_group");
+
+        self.batch(DrawKind::GroupEnd, index);
+
+        self.group_depth -= 1;
+
+        if self.group_depth == 0 {
+            self.group_layer = None;
+        }
+    }
+
+    ///
+    /// Clears the group's target and makes it the current msaa target.
+    /// Returns the group's view, which following batches draw into.
+    ///
+    pub(crate) fn flush_group_begin(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        index: usize,
+    ) -> Arc<wgpu::TextureView> {
+        let target = self.group_render.target(device, index);
+        let view = target.view().clone();
+
+        mem::swap(&mut self.msaa, &mut target.msaa);
+
+        let (target, resolve_target) = color_target(&self.msaa, &view);
+
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                }
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        view
+    }
+
+    ///
+    /// Restores the parent's msaa target and composites the group into
+    /// the parent view, or copies it into its image for an image group.
+    ///
+    pub(crate) fn flush_group_end(
+        &mut self,
+        device: &wgpu::Device,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        scissor: Option<(u32, u32, u32, u32)>,
+        index: usize,
+    ) {
+        let target = self.group_render.target(device, index);
+
+        mem::swap(&mut self.msaa, &mut target.msaa);
+
+        self.group_render.filter(device, encoder, index);
+
+        if let Some(image) = self.group_render.image(index) {
+            if let Some(image_view) = self.image_render.target_view(image) {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &image_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: wgpu::StoreOp::Store,
+                        }
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                self.group_render.composite(&mut rpass, index);
+            }
+
+            return;
+        }
+
+        let (target, resolve_target) = color_target(&self.msaa, view);
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        if let Some((x, y, w, h)) = scissor {
+            rpass.set_scissor_rect(x, y, w, h);
+        }
+
+        self.group_render.composite(&mut rpass, index);
+    }
+
+    fn batch(&mut self, kind: DrawKind, index: usize) {
+        let layer = self.group_layer.unwrap_or(self.layer);
+
+        if let Some(last) = self.batches.last_mut() {
+            if last.kind == kind 
+                && last.layer == layer
+                && last.scissor == self.scissor
+                && last.items.end == index
+                && kind.is_mergeable() {
+                last.items.end = index + 1;
+                return;
+            }
+        }
+
+        self.batches.push(DrawBatch {
+            kind,
+            layer,
+            scissor: self.scissor,
+            items: index..index + 1,
+        });
+    }
+
+    fn batch_path(&mut self) {
+        let index = self.shape2d_render.item_count() - 1;
+
+        if self.is_antialias || self.msaa.is_none() {
+            self.batch(DrawKind::Path, index);
+        } else {
+            self.batch(DrawKind::AliasPath, index);
+        }
+    }
+
+    ///
+    /// Flushes a range of path items. Shape and bezier items are created in
+    /// pairs, so both renderers share a single pass and alternate per item.
+    /// 
+    /// Non-antialiased paths with msaa are drawn into the single-sample
+    /// alias texture, which is then composited into the msaa target.
+    ///
+    pub(crate) fn flush_paths(
+        &mut self,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        scissor: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
+        is_alias: bool,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+
+        let (target, resolve_target, load) = match &self.msaa {
+            Some(msaa) if is_alias => {
+                (msaa.alias_view(), None, wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT))
+            }
+            _ => {
+                let (target, resolve_target) = color_target(&self.msaa, view);
+
+                (target, resolve_target, wgpu::LoadOp::Load)
+            }
+        };
+
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    }
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            if let Some((x, y, w, h)) = scissor {
+                rpass.set_scissor_rect(x, y, w, h);
+            }
+
+            // shape2d and bezier pipelines share the gradient table layout
+            rpass.set_bind_group(0, self.gradient_lut.bind_group(), &[]);
+
+            for i in items {
+                self.shape2d_render.draw_item(&mut rpass, i, is_alias);
+                self.bezier_render.draw_item(&mut rpass, i, is_alias);
+            }
+        }
+
+        if let Some(msaa) = &self.msaa {
+            if is_alias {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: msaa.view(),
+                        resolve_target: Some(view),
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: wgpu::StoreOp::Store,
+                        }
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                if let Some((x, y, w, h)) = scissor {
+                    rpass.set_scissor_rect(x, y, w, h);
+                }
+
+                msaa.composite(&mut rpass);
+            }
+        }
     }
 
     pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
@@ -112,6 +505,28 @@ impl PlotCanvas {
         self.to_gpu = self.bounds.affine_to(&pos_gpu);
 
         self.form3d_render.resize(device, width, height);
+
+        if let Some(msaa) = &mut self.msaa {
+            msaa.resize(device, width, height);
+        }
+
+        self.group_render.resize(width, height);
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    ///
+    /// Returns the render pass attachment and resolve target for the 
+    /// output view.
+    ///
+    pub(crate) fn color_target<'a>(
+        &'a self, 
+        view: &'a wgpu::TextureView
+    ) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+        color_target(&self.msaa, view)
     }
 
     pub fn to_scissor(&self, clip: &Clip) -> Option<(u32, u32, u32, u32)> {
@@ -158,6 +573,7 @@ impl PlotCanvas {
     ) {
         self.shape2d_render.start_shape(None);
         self.bezier_render.start_shape();
+        self.batch_path();
 
         // let mut points = Vec::<Point>::new();
         let mut last = Point(0., 0.);
@@ -186,6 +602,7 @@ impl PlotCanvas {
         texture: TextureId,
     ) {
         self.shape2d_texture_render.start_shape(texture, None);
+        self.batch(DrawKind::Texture, self.shape2d_texture_render.item_count() - 1);
         // TODO: bezier
 
         let triangles = triangulate2(path);
@@ -214,6 +631,11 @@ impl PlotCanvas {
             None => JoinStyle::Bevel,
         };
 
+        let miter_limit = match style.get_miter_limit() {
+            Some(miter_limit) => *miter_limit,
+            None => 4.,
+        };
+
         let capstyle  = match style.get_cap_style() {
             Some(capstyle) => capstyle.clone(),
             None => CapStyle::Butt,
@@ -224,6 +646,7 @@ impl PlotCanvas {
         
         self.shape2d_render.start_shape(None);
         self.bezier_render.start_shape();
+        self.batch_path();
 
         let mut p0 = Point(0.0f32, 0.0f32);
         let mut p_move = p0;
@@ -259,14 +682,14 @@ impl PlotCanvas {
                     self.shape2d_render.draw_line(&p0, p1, lw2);
                     self.shape2d_render.draw_line(p1, &p_move, lw2);
 
-                    self.join_lines(p0, *p1, p_move, lw2, &joinstyle);
-                    self.join_lines(*p1, p_move, p_first, lw2, &joinstyle);
+                    self.join_lines(p0, *p1, p_move, lw2, &joinstyle, miter_limit);
+                    self.join_lines(*p1, p_move, p_first, lw2, &joinstyle, miter_limit);
 
                     *p1
                 }
             };
 
-            self.join_lines(p_last, p0, p_next, lw2, &joinstyle);
+            self.join_lines(p_last, p0, p_next, lw2, &joinstyle, miter_limit);
 
             if p_first == p_move {
                 p_first = p_next;
@@ -284,7 +707,8 @@ impl PlotCanvas {
         b1: Point, 
         b2: Point, 
         lw2: f32, 
-        join_style: &JoinStyle
+        join_style: &JoinStyle,
+        miter_limit: f32,
     ) {
         let min_join = 1.;
 
@@ -293,8 +717,8 @@ impl PlotCanvas {
             return;
         }
 
-        self.join_lines_sign(b0, b1, b2, lw2, join_style, 1.);
-        self.join_lines_sign(b0, b1, b2, lw2, join_style, -1.);
+        self.join_lines_sign(b0, b1, b2, lw2, join_style, miter_limit, 1.);
+        self.join_lines_sign(b0, b1, b2, lw2, join_style, miter_limit, -1.);
     }
 
 
@@ -305,6 +729,7 @@ impl PlotCanvas {
         b2: Point,
         lw2: f32, 
         join_style: &JoinStyle,
+        miter_limit: f32,
         sign: f32,
     ) {
         let (nx, ny) = line_normal(b0, b1, lw2);
@@ -327,12 +752,8 @@ impl PlotCanvas {
         match join_style {
             JoinStyle::Bevel => {},
             JoinStyle::Miter => {
-                // TODO: clamp intersections of too-long length
-                let mp = line_intersection(p0, p1, q1, q2);
-
-                if mp != p0 { // non-parallel
-                    let mp = clamp_miter(b1, mp, lw2 * 2.);
-
+                // beyond the limit, the join falls back to the bevel
+                if let Some(mp) = miter_point(b1, p1, q1, lw2, miter_limit) {
                     self.shape2d_render.draw_triangle(&p1, &mp, &q1);
                 }
             },
@@ -398,6 +819,11 @@ impl PlotCanvas {
     ) -> Result<(), RenderErr> {
         // let to_unit = self.to_gpu.matmul(to_device);
 
+        self.is_antialias = match style.get_antialias() {
+            Some(is_antialias) => *is_antialias,
+            None => true,
+        };
+
         let face_color = match style.get_face_color() {
             Some(color) => *color,
             None => Color(0x000000ff)
@@ -408,9 +834,38 @@ impl PlotCanvas {
             None => face_color
         };
 
-        let path = match style.get_line_style() {
+        let face_paint = match style.get_face_gradient() {
+            Some(gradient) => Some(self.gradient_lut.paint(gradient)),
+            None => None,
+        };
+
+        let edge_paint = match style.get_edge_gradient() {
+            Some(gradient) => Some(self.gradient_lut.paint(gradient)),
+            None => None,
+        };
+
+        let arrow_src = path;
+
+        // shorten the stroke so it ends inside the arrowheads
+        let arrow_path;
+        let path = match (style.get_arrow_start(), style.get_arrow_end()) {
+            (None, None) => path,
+            _ if path.is_closed_path() => path,
+            (start, end) => {
+                let lw = self.stroke_width(style);
+
+                arrow_path = path.shorten(
+                    start.map_or(0., |arrow| arrow.shorten(lw)),
+                    end.map_or(0., |arrow| arrow.shorten(lw)),
+                );
+
+                &arrow_path
+            }
+        };
+
+        let (path, gap_path) = match style.get_line_style() {
             Some(LineStyle::Solid) | None => {
-                transform_solid_path(path)
+                (transform_solid_path(path), None)
             }
             Some(line_style) => {
                 let lw = match style.get_line_width() {
@@ -420,19 +875,66 @@ impl PlotCanvas {
                 
                 let pattern = line_style.to_pattern(lw);
 
-                transform_dashed_path(path, pattern)
+                if pattern.iter().sum::<f32>() <= 0. {
+                    (transform_solid_path(path), None)
+                } else {
+                    let offset = match style.get_dash_offset() {
+                        Some(offset) => lw * *offset,
+                        None => 0.,
+                    };
+
+                    // the gap path is the inverse pattern, starting at the first gap
+                    let gap_path = match style.get_gap_color() {
+                        Some(_) => {
+                            let mut gap_pattern = pattern.clone();
+                            gap_pattern.rotate_left(1);
+
+                            Some(transform_dashed_path(path, gap_pattern, offset - pattern[0]))
+                        }
+                        None => None,
+                    };
+
+                    (transform_dashed_path(path, pattern, offset), gap_path)
+                }
             },
         };
 
-        if path.is_closed_path() && ! face_color.is_none() {
+        if let (Some(gap_path), Some(gap_color)) = (&gap_path, style.get_gap_color()) {
+            self.draw_lines(gap_path, style);
+
+            self.shape2d_render.draw_style(*gap_color, &self.to_gpu);
+            self.bezier_render.draw_style(*gap_color, &self.to_gpu);
+        }
+
+        let is_fill = ! face_color.is_none() 
+            || face_paint.is_some()
+            || style.get_hatch().is_some() && style.get_hatch_color().is_some();
+
+        if path.is_closed_path() && is_fill {
             let mut is_texture = true;
 
             if let Some(hatch) = style.get_hatch() {
-                let texture = self.shape2d_texture_render.hatch_texture(*hatch);
+                // with a hatch color, the face is filled under the hatch
+                let hatch_color = match style.get_hatch_color() {
+                    Some(hatch_color) => {
+                        if ! face_color.is_none() || face_paint.is_some() {
+                            self.fill_path(&path);
+
+                            self.draw_paint_style(face_color, face_paint);
+                        }
+
+                        *hatch_color
+                    }
+                    None => face_color,
+                };
 
-                self.fill_texture_path(&path, texture);
+                let lw = self.hatch_line_width(style);
 
-                self.shape2d_texture_render.draw_style(face_color, &self.to_gpu);
+                if let Some(texture) = self.shape2d_texture_render.hatch_texture(*hatch, lw) {
+                    self.fill_texture_path(&path, texture);
+
+                    self.shape2d_texture_render.draw_style(hatch_color, &self.to_gpu);
+                }
                 //self.bezier_render.draw_style(face_color, &self.to_gpu);
 
                 is_texture = true;
@@ -440,33 +942,123 @@ impl PlotCanvas {
                 self.fill_texture_path(&path, *texture);
     
                 self.shape2d_texture_render.draw_style(face_color, &self.to_gpu);
-                self.bezier_render.draw_style(face_color, &self.to_gpu);
 
                 is_texture = true;
             } else {
                 self.fill_path(&path);
 
-                self.shape2d_render.draw_style(face_color, &self.to_gpu);
-                self.bezier_render.draw_style(face_color, &self.to_gpu);
+                self.draw_paint_style(face_color, face_paint);
             }
 
-            if face_color != edge_color || is_texture {
+            if face_color != edge_color || is_texture || edge_paint.is_some() {
                 self.draw_lines(&path, style);
 
-                self.shape2d_render.draw_style(edge_color, &self.to_gpu);
-                self.bezier_render.draw_style(edge_color, &self.to_gpu);
+                self.draw_paint_style(edge_color, edge_paint);
             }
         } else {
             self.draw_lines(&path, style);
 
-            self.shape2d_render.draw_style(edge_color, &self.to_gpu);
-            self.bezier_render.draw_style(edge_color, &self.to_gpu);
+            self.draw_paint_style(edge_color, edge_paint);
         }
 
+        self.draw_arrows(arrow_src, style, edge_color, edge_paint);
 
         return Ok(());
     }
 
+    ///
+    /// Draws the current shape2d and bezier shapes with a solid color, or
+    /// with a gradient paint when it's available.
+    ///
+    fn draw_paint_style(&mut self, color: Color, paint: Option<PaintStyle>) {
+        match paint {
+            Some(paint) => {
+                let white = Color::white();
+
+                self.shape2d_render.draw_style_paint(white, paint, &self.to_gpu);
+                self.bezier_render.draw_style_paint(white, paint, &self.to_gpu);
+            }
+            None => {
+                self.shape2d_render.draw_style(color, &self.to_gpu);
+                self.bezier_render.draw_style(color, &self.to_gpu);
+            }
+        }
+    }
+
+    fn draw_arrows(
+        &mut self,
+        path: &Path<Canvas>,
+        style: &dyn PathOpt,
+        color: Color,
+        paint: Option<PaintStyle>,
+    ) {
+        if path.is_closed_path() || (color.is_none() && paint.is_none()) {
+            return;
+        }
+
+        let lw = self.stroke_width(style);
+
+        // stroked arrowheads need sharp tips
+        let mut arrow_style = PathStyleBase::new();
+        arrow_style.join_style(JoinStyle::Miter);
+        let arrow_style = arrow_style.push(style);
+
+        let ends = [
+            (style.get_arrow_start(), path.start_tangent()),
+            (style.get_arrow_end(), path.end_tangent()),
+        ];
+
+        for (arrow, tangent) in ends {
+            if let (Some(arrow), Some((tip, dir))) = (arrow, tangent) {
+                let arrow_path = arrow.path(tip, dir, lw);
+
+                if arrow.is_fill() {
+                    self.fill_path(&arrow_path);
+                } else {
+                    self.draw_lines(&arrow_path, &arrow_style);
+                }
+
+                self.draw_paint_style(color, paint);
+            }
+        }
+    }
+
+    ///
+    /// Creates the hatch texture for the style, which needs the device, so
+    /// the renderer calls it before draw_path.
+    ///
+    pub(crate) fn prepare_hatch(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        style: &dyn PathOpt,
+    ) {
+        if let Some(hatch) = style.get_hatch() {
+            let lw = self.hatch_line_width(style);
+
+            self.shape2d_texture_render.add_hatch(device, queue, *hatch, lw);
+        }
+    }
+
+    // hatch line width in pixels, where the default is the 4 device pixels
+    // of the original fixed hatch textures
+    fn hatch_line_width(&self, style: &dyn PathOpt) -> f32 {
+        match style.get_hatch_line_width() {
+            Some(lw) => self.to_px(*lw),
+            None => 4.,
+        }
+    }
+
+    // stroke width in pixels, matching draw_lines
+    fn stroke_width(&self, style: &dyn PathOpt) -> f32 {
+        let linewidth = match style.get_line_width() {
+            Some(linewidth) => *linewidth,
+            None => 0.5,
+        };
+
+        2. * self.to_px(0.5 * linewidth).max(0.5)
+    }
+
     pub fn draw_markers(
         &mut self, 
         path: &Path<Canvas>, 
@@ -477,6 +1069,11 @@ impl PlotCanvas {
     ) -> Result<(), RenderErr> {
         let path = transform_solid_path(path);
 
+        self.is_antialias = match style.get_antialias() {
+            Some(is_antialias) => *is_antialias,
+            None => true,
+        };
+
         let face_color = match style.get_face_color() {
             Some(color) => *color,
             None => Color(0x000000ff)
@@ -584,6 +1181,7 @@ impl PlotCanvas {
             halign,
             valign,
         );
+        self.batch(DrawKind::Text, self.text_render.item_count() - 1);
  
         Ok(())
     }
@@ -611,6 +1209,7 @@ impl PlotCanvas {
             triangles.shape().as_slice());
 
         self.triangle_render.start_triangles();
+        self.batch(DrawKind::Triangles, self.triangle_render.item_count() - 1);
 
         for (xy, color) in vertices.iter_row().zip(rgba.iter()) {
             self.triangle_render.draw_vertex(xy[0], xy[1], *color);
@@ -639,6 +1238,7 @@ impl PlotCanvas {
     ) -> Result<(), RenderErr> {
         self.form3d_render.camera(camera);
         self.form3d_render.draw_form(form);
+        self.batch(DrawKind::Form3d, self.form3d_render.item_count() - 1);
         
         Ok(())
     }
@@ -658,6 +1258,7 @@ impl PlotCanvas {
         let camera = camera.compose(&self.to_gpu);
         self.shape2d_tex2_render.camera(&camera);
         self.shape2d_tex2_render.draw_shape(shape);
+        self.batch(DrawKind::Shape, self.shape2d_tex2_render.item_count() - 1);
         
         Ok(())
     }
@@ -673,6 +1274,7 @@ impl PlotCanvas {
         assert!(colors.cols() == 4, "colors must have 4-width columns shape={:?}", colors.shape().as_slice());
 
         self.image_render._draw(device, bounds, colors, &self.to_gpu);
+        self.batch(DrawKind::Image, self.image_render.item_count() - 1);
 
         Ok(())
     }
@@ -716,6 +1318,20 @@ impl PlotCanvas {
         image: ImageId,    // N in rgba
     ) -> Result<(), RenderErr> {
         self.image_render.draw_image(device, pos, &image, &self.to_gpu);
+        self.batch(DrawKind::Image, self.image_render.item_count() - 1);
+
+        Ok(())
+    }
+
+    pub fn draw_scalar_image(
+        &mut self,
+        device: &wgpu::Device,
+        pos: &Bounds<Canvas>,
+        values: &Tensor<f32>,  // [rows, cols]
+        map: &ScalarMap,
+    ) -> Result<(), RenderErr> {
+        self.scalar_image_render.draw(device, pos, values, map, &self.to_gpu)?;
+        self.batch(DrawKind::ScalarImage, self.scalar_image_render.item_count() - 1);
 
         Ok(())
     }
@@ -747,6 +1363,76 @@ impl PlotCanvas {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DrawKind {
+    Path,
+    AliasPath,
+    Texture,
+    Text,
+    Triangles,
+    Image,
+    ScalarImage,
+    Form3d,
+    Shape,
+    GroupBegin,
+    GroupEnd,
+}
+
+impl DrawKind {
+    ///
+    /// Form and shape items each carry their own camera uniform, so they
+    /// can't share a pass with neighboring items. Group markers switch the
+    /// render target, so each one is its own batch.
+    ///
+    pub(crate) fn is_mergeable(&self) -> bool {
+        match self {
+            DrawKind::Form3d | DrawKind::Shape => false,
+            DrawKind::GroupBegin | DrawKind::GroupEnd => false,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct DrawBatch {
+    pub(crate) kind: DrawKind,
+    pub(crate) layer: Layer,
+    pub(crate) scissor: Option<(u32, u32, u32, u32)>,
+    pub(crate) items: Range<usize>,
+}
+
+///
+/// Returns the miter tip for a join at center, where p1 and q1 are the 
+/// offset points of the two segments. Follows SVG stroke-miterlimit: 
+/// when the ratio of the miter length to the line width exceeds the limit,
+/// returns None for a bevel.
+///
+fn miter_point(center: Point, p1: Point, q1: Point, lw2: f32, miter_limit: f32) -> Option<Point> {
+    // half-way vector between the two offsets, length lw2 * cos(theta / 2)
+    let hx = 0.5 * (p1.x() + q1.x()) - center.x();
+    let hy = 0.5 * (p1.y() + q1.y()) - center.y();
+
+    let h_len = hx.hypot(hy);
+
+    if h_len <= f32::EPSILON || lw2 <= 0. {
+        return None;
+    }
+
+    // miter_length / line_width = 1 / cos(theta / 2)
+    let ratio = lw2 / h_len;
+
+    if ratio > miter_limit {
+        None
+    } else if ratio <= 1. + f32::EPSILON {
+        // straight continuation
+        None
+    } else {
+        let scale = lw2 * ratio / h_len;
+
+        Some(Point(center.x() + hx * scale, center.y() + hy * scale))
+    }
+}
+
 fn clamp_miter(center: Point, miter: Point, lim: f32) -> Point {
     Point(
         miter.0.clamp(center.0 - lim, center.0 + lim),
@@ -900,13 +1586,19 @@ fn marker_color(i: usize, color: &Tensor<u32>, default: Color) -> Color {
     }
 }
 
-fn transform_dashed_path(path: &Path<Canvas>, pattern: Vec<f32>) -> Path<Canvas> {
+fn transform_dashed_path(path: &Path<Canvas>, pattern: Vec<f32>, offset: f32) -> Path<Canvas> {
+    // like SVG stroke-dasharray, a pattern that can't advance is solid
+    if pattern.iter().any(|v| ! v.is_finite() || *v < 0.)
+        || pattern.iter().sum::<f32>() <= 0. {
+        return transform_solid_path(path);
+    }
+
     let mut codes = Vec::<PathCode>::new();
 
     let mut p0 = Point(0.0f32, 0.0f32);
     let mut moveto = p0;
 
-    let mut cursor = Cursor::new(pattern);
+    let mut cursor = DashCursor::new(pattern, offset);
 
     for code in path.codes() {
         p0 = match code {
@@ -940,7 +1632,7 @@ fn transform_dashed_path(path: &Path<Canvas>, pattern: Vec<f32>) -> Path<Canvas>
 
 fn add_dash_line(
     codes: &mut Vec::<PathCode>, 
-    cursor: &mut Cursor,
+    cursor: &mut DashCursor,
     p0: Point,
     p1: Point,
 ) -> Point {
@@ -991,30 +1683,62 @@ fn add_dash_line(
     p1
 }   
 
-struct Cursor {
+struct DashCursor {
     dashes: Vec<f32>,
+    offset: f32,
     i: usize,
     t: f32,
+    is_reset: bool,
 }
 
-impl Cursor {
-    fn new(pattern: Vec<f32>) -> Self {
-        Self {
+impl DashCursor {
+    fn new(pattern: Vec<f32>, offset: f32) -> Self {
+        let mut cursor = Self {
             dashes: pattern,
+            offset,
             i: 0,
             t: 0.,
-        }
+            is_reset: true,
+        };
+
+        cursor.reset();
+
+        cursor
     }
 
-    #[inline]
+    ///
+    /// Restarts the pattern at the dash offset (phase) for a new sub-path.
+    ///
     fn reset(&mut self) {
         self.i = 0;
         self.t = 0.;
+        self.is_reset = true;
+
+        let total: f32 = self.dashes.iter().sum();
+
+        let mut phase = if self.offset.is_finite() {
+            self.offset.rem_euclid(total)
+        } else {
+            0.
+        };
+
+        while phase > 0. {
+            let dash = self.dashes[self.i];
+
+            if phase < dash {
+                self.t = phase;
+                break;
+            }
+
+            phase -= dash;
+            self.i = (self.i + 1) % self.dashes.len();
+        }
     }
 
     #[inline]
     fn is_start(&mut self) -> bool {
-        self.t == 0.
+        // an offset can start a sub-path in the middle of a dash
+        self.t == 0. || mem::take(&mut self.is_reset)
     }
 
     #[inline]
@@ -1025,6 +1749,7 @@ impl Cursor {
     #[inline]
     fn add(&mut self, len: f32) {
         self.t += len;
+        self.is_reset = false;
     }
 
     #[inline]
@@ -1036,5 +1761,134 @@ impl Cursor {
     fn next(&mut self) {
         self.i = (self.i + 1) % self.dashes.len();
         self.t = 0.;
+        self.is_reset = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use essay_graphics_api::Point;
+
+    use essay_graphics_api::{renderer::Canvas, Path, PathCode};
+
+    use super::{line_normal, miter_point, transform_dashed_path, transform_solid_path, DashCursor};
+
+    #[test]
+    fn miter_right_angle() {
+        let (p1, q1) = offsets(Point(0., 0.), Point(10., 0.), Point(10., 10.));
+
+        // ratio is sqrt(2), within the SVG default of 4
+        let tip = miter_point(Point(10., 0.), p1, q1, 1., 4.).unwrap();
+        assert!(tip.dist(&Point(11., -1.)) < 1e-4);
+
+        // sqrt(2) is past a limit of 1.2
+        assert_eq!(miter_point(Point(10., 0.), p1, q1, 1., 1.2), None);
+    }
+
+    #[test]
+    fn miter_acute_angle() {
+        let (p1, q1) = offsets(Point(0., 0.), Point(10., 0.), Point(0., 1.));
+
+        // ratio is about 20, so the join is a bevel
+        assert_eq!(miter_point(Point(10., 0.), p1, q1, 1., 4.), None);
+
+        assert!(miter_point(Point(10., 0.), p1, q1, 1., 30.).is_some());
+    }
+
+    #[test]
+    fn miter_collinear_reversed() {
+        let (p1, q1) = offsets(Point(0., 0.), Point(10., 0.), Point(20., 0.));
+
+        assert_eq!(miter_point(Point(10., 0.), p1, q1, 1., 4.), None);
+
+        // doubling back has no finite miter
+        let (p1, q1) = offsets(Point(0., 0.), Point(10., 0.), Point(0., 0.));
+
+        assert_eq!(miter_point(Point(10., 0.), p1, q1, 1., 4.), None);
+        assert_eq!(miter_point(Point(10., 0.), p1, q1, 1., 1000.), None);
+    }
+
+    #[test]
+    fn dash_cursor() {
+        let cursor = DashCursor::new(vec![4., 2.], 0.);
+        assert_eq!((cursor.i, cursor.t, cursor.sublen()), (0, 0., 4.));
+
+        // the phase wraps past the pattern's total
+        let cursor = DashCursor::new(vec![4., 2.], 7.);
+        assert_eq!((cursor.i, cursor.t, cursor.sublen()), (0, 1., 3.));
+        assert!(cursor.is_visible());
+
+        // a negative phase counts back from the end
+        let cursor = DashCursor::new(vec![4., 2.], -1.);
+        assert_eq!((cursor.i, cursor.t, cursor.sublen()), (1, 1., 1.));
+        assert!(! cursor.is_visible());
+
+        let cursor = DashCursor::new(vec![4., 2.], f32::NAN);
+        assert_eq!((cursor.i, cursor.t), (0, 0.));
+    }
+
+    #[test]
+    fn dashed_path() {
+        let path = line(10.);
+
+        assert_eq!(transform_dashed_path(&path, vec![4., 2.], 0.).codes(), &vec![
+            PathCode::MoveTo(Point(0., 0.)),
+            PathCode::LineTo(Point(4., 0.)),
+            PathCode::MoveTo(Point(6., 0.)),
+            PathCode::LineTo(Point(10., 0.)),
+        ]);
+
+        // dash offset (phase)
+        assert_eq!(transform_dashed_path(&path, vec![4., 2.], 1.).codes(), &vec![
+            PathCode::MoveTo(Point(0., 0.)),
+            PathCode::LineTo(Point(3., 0.)),
+            PathCode::MoveTo(Point(5., 0.)),
+            PathCode::LineTo(Point(9., 0.)),
+        ]);
+
+        assert_eq!(transform_dashed_path(&path, vec![4., 2.], -1.).codes(), &vec![
+            PathCode::MoveTo(Point(1., 0.)),
+            PathCode::LineTo(Point(5., 0.)),
+            PathCode::MoveTo(Point(7., 0.)),
+            PathCode::LineTo(Point(10., 0.)),
+        ]);
+
+        // gap color draws the rotated pattern, starting at the first gap
+        assert_eq!(transform_dashed_path(&path, vec![2., 4.], 0. - 4.).codes(), &vec![
+            PathCode::MoveTo(Point(4., 0.)),
+            PathCode::LineTo(Point(6., 0.)),
+        ]);
+    }
+
+    #[test]
+    fn dashed_path_degenerate() {
+        let path = line(10.);
+        let solid = transform_solid_path(&path);
+
+        for pattern in [vec![], vec![0., 0.], vec![4., -2.], vec![f32::INFINITY, 2.]] {
+            assert_eq!(
+                transform_dashed_path(&path, pattern.clone(), 0.).codes(), 
+                solid.codes(),
+                "pattern {:?}", pattern
+            );
+        }
+    }
+
+    fn line(len: f32) -> Path<Canvas> {
+        Path::new(vec![
+            PathCode::MoveTo(Point(0., 0.)),
+            PathCode::LineTo(Point(len, 0.)),
+        ])
+    }
+
+    // outside offset points at b1, as in join_lines_sign
+    fn offsets(b0: Point, b1: Point, b2: Point) -> (Point, Point) {
+        let (nx, ny) = line_normal(b0, b1, 1.);
+        let p1 = Point(b1.x() + nx, b1.y() - ny);
+
+        let (nx, ny) = line_normal(b1, b2, 1.);
+        let q1 = Point(b1.x() + nx, b1.y() - ny);
+
+        (p1, q1)
     }
 }
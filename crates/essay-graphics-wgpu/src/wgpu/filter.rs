@@ -0,0 +1,332 @@
+use bytemuck_derive::{Pod, Zeroable};
+use essay_graphics_api::{Color, Filter};
+use wgpu::util::DeviceExt;
+
+use super::msaa::color_texture;
+
+///
+/// Blur and drop shadow filters for group targets, each drawn as two
+/// separable blur passes through a temporary texture. The second pass
+/// writes back into the group target, which is then composited as usual.
+///
+pub(crate) struct FilterRender {
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+
+    temp: Option<(wgpu::TextureView, wgpu::BindGroup)>,
+
+    style_vec: Vec<FilterStyle>,
+    style_offset: usize,
+    style_buffer: wgpu::Buffer,
+    is_stale: bool,
+
+    blur_pipeline: wgpu::RenderPipeline,
+    shadow_pipeline: wgpu::RenderPipeline,
+}
+
+impl FilterRender {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let len = 16;
+
+        let mut style_vec = Vec::<FilterStyle>::new();
+        style_vec.resize(len, FilterStyle::empty());
+
+        let style_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(style_vec.as_slice()),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        // the blur replaces the target, and the shadow is drawn under it
+        let blur_pipeline = create_filter_pipeline(device, layout, format, None);
+
+        let shadow_pipeline = create_filter_pipeline(device, layout, format, Some(
+            wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }
+        ));
+
+        Self {
+            format,
+            width,
+            height,
+
+            temp: None,
+
+            style_vec,
+            style_offset: 0,
+            style_buffer,
+            is_stale: false,
+
+            blur_pipeline,
+            shadow_pipeline,
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.style_offset = 0;
+    }
+
+    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+
+        self.temp = None;
+    }
+
+    ///
+    /// Adds the filter's two passes and returns the index of the first.
+    ///
+    pub(crate) fn push(&mut self, filter: &Filter, scale_factor: f32) -> usize {
+        let sigma = filter.sigma(scale_factor);
+
+        let (first, second) = match filter {
+            Filter::Blur(_) => (
+                FilterStyle::new([1., 0., sigma, 0.], [0., 0.], Color::none()),
+                FilterStyle::new([0., 1., sigma, 0.], [0., 0.], Color::none()),
+            ),
+            Filter::DropShadow { color, .. } => {
+                // target rows are top to bottom
+                let offset = filter.offset(scale_factor);
+                let offset = [offset.x(), offset.y()];
+
+                (
+                    FilterStyle::new([1., 0., sigma, 1.], offset, Color::none()),
+                    FilterStyle::new([0., 1., sigma, 2.], [0., 0.], *color),
+                )
+            }
+        };
+
+        let index = self.style_offset;
+
+        while self.style_offset + 2 > self.style_vec.len() {
+            self.is_stale = true;
+            self.style_vec.resize(2 * self.style_vec.len(), FilterStyle::empty());
+        }
+
+        self.style_vec[index] = first;
+        self.style_vec[index + 1] = second;
+        self.style_offset += 2;
+
+        index
+    }
+
+    pub(crate) fn is_shadow(&self, index: usize) -> bool {
+        self.style_vec[index].params[3] == 1.
+    }
+
+    ///
+    /// Writes the style buffer before any filter passes
+    ///
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        if self.style_offset == 0 {
+            return;
+        }
+
+        if self.is_stale {
+            self.is_stale = false;
+
+            self.style_buffer = device.create_buffer_init(
+                &wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(self.style_vec.as_slice()),
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                }
+            );
+        }
+
+        queue.write_buffer(
+            &mut self.style_buffer,
+            0,
+            bytemuck::cast_slice(self.style_vec.as_slice())
+        );
+    }
+
+    ///
+    /// Filters the group target in place, where bind_group reads view.
+    ///
+    pub(crate) fn flush(
+        &mut self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        bind_group: &wgpu::BindGroup,
+        index: usize,
+    ) {
+        if self.temp.is_none() {
+            let temp_view = color_texture(device, self.format, self.width, self.height, 1)
+                .create_view(&wgpu::TextureViewDescriptor::default());
+
+            let temp_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&temp_view),
+                    },
+                ],
+                label: Some("filter_bind_group"),
+            });
+
+            self.temp = Some((temp_view, temp_bind_group));
+        }
+
+        let (temp_view, temp_bind_group) = self.temp.as_ref().unwrap();
+
+        {
+            let mut rpass = begin_pass(encoder, temp_view, wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT));
+
+            rpass.set_pipeline(&self.blur_pipeline);
+            rpass.set_bind_group(0, bind_group, &[]);
+            rpass.set_vertex_buffer(0, self.style_buffer.slice(..));
+            rpass.draw(0..3, index as u32..index as u32 + 1);
+        }
+
+        {
+            let mut rpass = begin_pass(encoder, view, wgpu::LoadOp::Load);
+
+            if self.is_shadow(index) {
+                rpass.set_pipeline(&self.shadow_pipeline);
+            } else {
+                rpass.set_pipeline(&self.blur_pipeline);
+            }
+
+            rpass.set_bind_group(0, temp_bind_group, &[]);
+            rpass.set_vertex_buffer(0, self.style_buffer.slice(..));
+            rpass.draw(0..3, index as u32 + 1..index as u32 + 2);
+        }
+    }
+}
+
+fn begin_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    view: &'a wgpu::TextureView,
+    load: wgpu::LoadOp<wgpu::Color>,
+) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            }
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    })
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct FilterStyle {
+    // direction, sigma, mode
+    params: [f32; 4],
+    offset: [f32; 4],
+    // premultiplied linear shadow color
+    color: [f32; 4],
+}
+
+impl FilterStyle {
+    const ATTRS: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4
+        ];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<FilterStyle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRS,
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            params: [0., 0., 0., 0.],
+            offset: [0., 0., 0., 0.],
+            color: [0., 0., 0., 0.],
+        }
+    }
+
+    fn new(params: [f32; 4], offset: [f32; 2], color: Color) -> Self {
+        let [r, g, b, a] = color.to_lrgb();
+
+        Self {
+            params,
+            offset: [offset[0], offset[1], 0., 0.],
+            color: [r * a, g * a, b * a, a],
+        }
+    }
+}
+
+fn create_filter_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    blend: Option<wgpu::BlendState>,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("filter.wgsl"));
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[
+            layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_filter",
+            buffers: &[
+                FilterStyle::desc(),
+            ],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_filter",
+            targets: &[
+                Some(wgpu::ColorTargetState {
+                    format,
+                    blend,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            ],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
@@ -0,0 +1,74 @@
+@group(0) @binding(0)
+var t_src: texture_2d<f32>;
+
+struct FilterStyle {
+    // direction, sigma, mode
+    @location(0) params: vec4<f32>,
+    @location(1) offset: vec4<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) @interpolate(flat) params: vec4<f32>,
+    @location(1) @interpolate(flat) offset: vec2<f32>,
+    @location(2) @interpolate(flat) color: vec4<f32>,
+};
+
+// single triangle covering the full viewport
+@vertex
+fn vs_filter(
+    @builtin(vertex_index) index: u32,
+    style: FilterStyle,
+) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.pos = vec4<f32>(x, y, 0.0, 1.0);
+    out.params = style.params;
+    out.offset = style.offset.xy;
+    out.color = style.color;
+    return out;
+}
+
+// one direction of a separable Gaussian blur over a premultiplied texture,
+// where texels outside the texture are transparent.
+//
+// mode 0 blurs the color, mode 1 blurs the offset alpha for a shadow,
+// and mode 2 blurs the alpha and fills it with the shadow color.
+@fragment
+fn fs_filter(
+    in: VertexOutput,
+) -> @location(0) vec4<f32> {
+    let dims = vec2<i32>(textureDimensions(t_src));
+    let dir = in.params.xy;
+    let sigma = max(in.params.z, 0.001);
+    let mode = i32(in.params.w);
+
+    let xy = in.pos.xy - in.offset;
+    let n = i32(ceil(3.0 * in.params.z));
+
+    var sum = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var w_sum = 0.0;
+
+    for (var k = -n; k <= n; k++) {
+        let w = exp(-0.5 * f32(k * k) / (sigma * sigma));
+        let p = vec2<i32>(floor(xy + dir * f32(k)));
+
+        if (all(p >= vec2<i32>(0, 0)) && all(p < dims)) {
+            sum += w * textureLoad(t_src, p, 0);
+        }
+
+        w_sum += w;
+    }
+
+    let color = sum / w_sum;
+
+    if (mode == 1) {
+        return vec4<f32>(color.a, color.a, color.a, color.a);
+    } else if (mode == 2) {
+        return color.a * in.color;
+    } else {
+        return color;
+    }
+}
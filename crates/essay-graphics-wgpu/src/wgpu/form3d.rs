@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bytemuck_derive::{Pod, Zeroable};
 use essay_graphics_api::{form::{Form, FormId, Matrix4}, TextureId};
 use wgpu::util::DeviceExt;
@@ -42,6 +44,7 @@ impl Form3dRender {
         format: wgpu::TextureFormat,
         width: u32,
         height: u32,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -92,11 +95,12 @@ impl Form3dRender {
             }
         );
 
-        let depth_buffer = DepthBuffer::new(device, width, height);
+        let depth_buffer = DepthBuffer::new(device, width, height, sample_count);
 
         let pipeline = form3d_pipeline(
             device, 
             format,
+            sample_count,
         );
     
         Self {
@@ -257,7 +261,7 @@ impl Form3dRender {
     }
 
     pub fn draw_form(&mut self, form: FormId) {
-        self.draw_items.push(DrawItem::new(form));
+        self.draw_items.push(DrawItem::new(form, self.camera));
     }
 
     pub fn camera(
@@ -267,16 +271,22 @@ impl Form3dRender {
         self.camera.set(camera);
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.draw_items.len()
+    }
+
     pub fn flush(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
         textures: &TextureCache,
-        clip: Option<(u32, u32, u32, u32)>
+        clip: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
     ) {
-        if self.draw_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -284,7 +294,7 @@ impl Form3dRender {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -354,10 +364,12 @@ impl Form3dRender {
             */
         }
 
+        let camera = self.draw_items[items.start].camera;
+
         queue.write_buffer(
             &mut self.camera_buffer,
             0,
-            bytemuck::cast_slice(&[self.camera])
+            bytemuck::cast_slice(&[camera])
         );
 
         rpass.set_pipeline(&self.pipeline);
@@ -369,7 +381,7 @@ impl Form3dRender {
             rpass.set_scissor_rect(x, y, w, h);
         }
 
-        for draw_item in self.draw_items.drain(..) {
+        for draw_item in &self.draw_items[items] {
             let item = &self.form_items[draw_item.id.0];
 
             /*
@@ -424,12 +436,15 @@ struct FormItem {
 
 struct DrawItem {
     id: FormId,
+    // camera at draw time, because items can be flushed after a later camera change
+    camera: CameraUniform,
 }
 
 impl DrawItem {
-    fn new(id: FormId) -> Self {
+    fn new(id: FormId, camera: CameraUniform) -> Self {
         Self {
             id,
+            camera,
         }
     }
 }
@@ -518,6 +533,7 @@ impl CameraUniform {
 fn form3d_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::include_wgsl!("form3d.wgsl"));
 
@@ -576,7 +592,10 @@ fn form3d_pipeline(
             stencil: wgpu::StencilState::default(),
             bias: wgpu::DepthBiasState::default(),
         }),
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
@@ -642,6 +661,7 @@ fn texture_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
 struct DepthBuffer {
     texture: wgpu::Texture,
     view: wgpu::TextureView,
+    sample_count: u32,
 }
 
 impl DepthBuffer {
@@ -651,23 +671,30 @@ impl DepthBuffer {
         device: &wgpu::Device, 
         width: u32,
         height: u32,
+        sample_count: u32,
     ) -> Self {
-        let texture = depth_texture(device, width, height);
+        let texture = depth_texture(device, width, height, sample_count);
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         Self {
             texture,
             view,
+            sample_count,
         }
     }
 
     fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
-        self.texture = depth_texture(device, width, height);
+        self.texture = depth_texture(device, width, height, self.sample_count);
         self.view = self.texture.create_view(&wgpu::TextureViewDescriptor::default());
     }
 }
 
-fn depth_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+fn depth_texture(
+    device: &wgpu::Device, 
+    width: u32, 
+    height: u32, 
+    sample_count: u32
+) -> wgpu::Texture {
     let size = wgpu::Extent3d {
         width,
         height,
@@ -678,7 +705,7 @@ fn depth_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Textur
         label: None,
         size,
         mip_level_count: 1,
-        sample_count: 1,
+        sample_count,
         dimension: wgpu::TextureDimension::D2,
         format: DepthBuffer::DEPTH_FORMAT,
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT
@@ -0,0 +1,199 @@
+use bytemuck_derive::{Pod, Zeroable};
+use essay_graphics_api::{Gradient, GradientShape, Spread};
+
+// samples per gradient in the lookup table, matching LUT_WIDTH in paint.wgsl
+const LUT_WIDTH: u32 = 256;
+
+///
+/// Lookup table texture for gradient paints, one row per gradient. The
+/// table is rebuilt every frame from the gradients drawn in that frame.
+///
+pub struct GradientLut {
+    gradients: Vec<Gradient>,
+    data: Vec<[f32; 4]>,
+
+    rows: u32,
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+impl GradientLut {
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        let rows = 16;
+        let (texture, bind_group) = create_lut_texture(device, rows);
+
+        Self {
+            gradients: Vec::new(),
+            data: Vec::new(),
+
+            rows,
+            texture,
+            bind_group,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.gradients.drain(..);
+        self.data.drain(..);
+    }
+
+    ///
+    /// Adds the gradient to the table if needed and returns the paint
+    /// style used by the shape2d and bezier shaders.
+    ///
+    pub(crate) fn paint(&mut self, gradient: &Gradient) -> PaintStyle {
+        let row = match self.gradients.iter().position(|g| g == gradient) {
+            Some(row) => row,
+            None => {
+                self.gradients.push(gradient.clone());
+
+                for color in gradient.to_lut(LUT_WIDTH as usize) {
+                    self.data.push(color.to_lrgb());
+                }
+
+                self.gradients.len() - 1
+            }
+        };
+
+        PaintStyle::new(gradient, row)
+    }
+
+    pub(crate) fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+
+    ///
+    /// Writes the table texture before any draw calls
+    ///
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        let rows = self.gradients.len() as u32;
+
+        if rows == 0 {
+            return;
+        }
+
+        if self.rows < rows {
+            self.rows = rows.next_power_of_two();
+
+            let (texture, bind_group) = create_lut_texture(device, self.rows);
+            self.texture = texture;
+            self.bind_group = bind_group;
+        }
+
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            bytemuck::cast_slice(self.data.as_slice()),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(LUT_WIDTH * 16),
+                rows_per_image: Some(rows),
+            },
+            wgpu::Extent3d {
+                width: LUT_WIDTH,
+                height: rows,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
+///
+/// Per-instance paint parameters. A kind of 0 is a solid color, 1 is a
+/// linear gradient and 2 is a radial gradient.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct PaintStyle {
+    pub(crate) geom: [f32; 4],
+    // kind, spread, lut row
+    pub(crate) mode: [f32; 4],
+}
+
+impl PaintStyle {
+    pub(crate) fn solid() -> Self {
+        Self {
+            geom: [0., 0., 0., 0.],
+            mode: [0., 0., 0., 0.],
+        }
+    }
+
+    fn new(gradient: &Gradient, row: usize) -> Self {
+        let (kind, geom) = match gradient.get_shape() {
+            GradientShape::Linear(p0, p1) => (1., [p0.x(), p0.y(), p1.x(), p1.y()]),
+            GradientShape::Radial(c, r) => (2., [c.x(), c.y(), *r, 0.]),
+        };
+
+        let spread = match gradient.get_spread() {
+            Spread::Pad => 0.,
+            Spread::Repeat => 1.,
+            Spread::Reflect => 2.,
+        };
+
+        Self {
+            geom,
+            mode: [kind, spread, row as f32, 0.],
+        }
+    }
+}
+
+pub(crate) fn lut_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                },
+                count: None,
+            },
+        ],
+        label: Some("gradient_lut_bind_group_layout"),
+    })
+}
+
+fn create_lut_texture(
+    device: &wgpu::Device,
+    rows: u32,
+) -> (wgpu::Texture, wgpu::BindGroup) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("gradient_lut"),
+        size: wgpu::Extent3d {
+            width: LUT_WIDTH,
+            height: rows,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba32Float,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout: &lut_bind_group_layout(device),
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+        ],
+        label: Some("gradient_lut_bind_group"),
+    });
+
+    (texture, bind_group)
+}
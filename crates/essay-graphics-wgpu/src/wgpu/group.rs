@@ -0,0 +1,511 @@
+use std::sync::Arc;
+
+use bytemuck_derive::{Pod, Zeroable};
+use essay_graphics_api::{renderer::{BlendMode, Group}, ImageId};
+use wgpu::util::DeviceExt;
+
+use super::{filter::FilterRender, image::IMAGE_FORMAT, msaa::{color_texture, MsaaTarget}};
+
+///
+/// Offscreen targets for draw groups. A group is drawn into a transparent
+/// texture, then composited into its parent with the group's opacity and
+/// blend mode.
+///
+/// Targets are pooled by nesting depth, because sibling groups are
+/// composited before the next group begins.
+/// 
+/// An image group is copied into a renderer-owned image instead of being
+/// composited, so the image can be drawn again in later frames.
+/// 
+/// A group's filter is applied to its target before compositing.
+///
+pub(crate) struct GroupRender {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    width: u32,
+    height: u32,
+
+    targets: Vec<GroupTarget>,
+    items: Vec<GroupItem>,
+
+    style_vec: Vec<GroupStyle>,
+    style_buffer: wgpu::Buffer,
+    is_stale: bool,
+
+    filter_render: FilterRender,
+
+    layout: wgpu::BindGroupLayout,
+    // indexed by blend_index
+    pipelines: Vec<wgpu::RenderPipeline>,
+    image_pipeline: wgpu::RenderPipeline,
+}
+
+impl GroupRender {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        let len = 16;
+
+        let mut style_vec = Vec::<GroupStyle>::new();
+        style_vec.resize(len, GroupStyle::empty());
+
+        let style_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(style_vec.as_slice()),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let layout = create_bind_group_layout(device);
+
+        let pipelines = [
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Additive,
+        ].iter().map(|blend| {
+            create_group_pipeline(device, &layout, format, sample_count, *blend)
+        }).collect();
+
+        let image_pipeline = create_image_pipeline(device, &layout);
+
+        let filter_render = FilterRender::new(device, &layout, format, width, height);
+
+        Self {
+            format,
+            sample_count,
+            width,
+            height,
+
+            targets: Vec::new(),
+            items: Vec::new(),
+
+            style_vec,
+            style_buffer,
+            is_stale: false,
+
+            filter_render,
+
+            layout,
+            pipelines,
+            image_pipeline,
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.items.drain(..);
+        self.filter_render.clear();
+    }
+
+    ///
+    /// Drops the pooled targets, which are recreated at the new size when
+    /// they're next used.
+    ///
+    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+
+        self.targets.drain(..);
+        self.filter_render.resize(width, height);
+    }
+
+    pub(crate) fn push(&mut self, group: &Group, depth: usize, scale_factor: f32) -> usize {
+        let filter = match group.get_filter() {
+            Some(filter) => Some(self.filter_render.push(&filter, scale_factor)),
+            None => None,
+        };
+
+        self.push_item(GroupStyle::new(group.get_opacity(), [0., 0.], 1.), GroupItem {
+            depth,
+            blend: group.get_blend(),
+            image: None,
+            filter,
+        })
+    }
+
+    ///
+    /// Adds an image group, where offset is the image's top-left corner in
+    /// the group target.
+    ///
+    pub(crate) fn push_image(
+        &mut self, 
+        image: ImageId, 
+        offset: [f32; 2], 
+        height: f32,
+        depth: usize
+    ) -> usize {
+        // image rows are bottom to top, so the copy reads the target upward
+        let offset = [offset[0], offset[1] + height];
+
+        self.push_item(GroupStyle::new(1., offset, -1.), GroupItem {
+            depth,
+            blend: BlendMode::Normal,
+            image: Some(image),
+            filter: None,
+        })
+    }
+
+    fn push_item(&mut self, style: GroupStyle, item: GroupItem) -> usize {
+        let index = self.items.len();
+
+        if index == self.style_vec.len() {
+            self.is_stale = true;
+            self.style_vec.resize(2 * self.style_vec.len(), GroupStyle::empty());
+        }
+
+        self.style_vec[index] = style;
+        self.items.push(item);
+
+        index
+    }
+
+    #[inline]
+    pub(crate) fn image(&self, index: usize) -> Option<&ImageId> {
+        self.items[index].image.as_ref()
+    }
+
+    ///
+    /// Writes the opacity and filter buffers before any composite calls
+    ///
+    pub(crate) fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        if self.items.len() == 0 {
+            return;
+        }
+
+        self.filter_render.prepare(device, queue);
+
+        if self.is_stale {
+            self.is_stale = false;
+
+            self.style_buffer = device.create_buffer_init(
+                &wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(self.style_vec.as_slice()),
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                }
+            );
+        }
+
+        queue.write_buffer(
+            &mut self.style_buffer,
+            0,
+            bytemuck::cast_slice(self.style_vec.as_slice())
+        );
+    }
+
+    ///
+    /// Returns the target for the group item, creating it if needed.
+    ///
+    pub(crate) fn target(&mut self, device: &wgpu::Device, index: usize) -> &mut GroupTarget {
+        let depth = self.items[index].depth;
+
+        while self.targets.len() <= depth {
+            let target = GroupTarget::new(
+                device,
+                &self.layout,
+                self.format,
+                self.width,
+                self.height,
+                self.sample_count
+            );
+
+            self.targets.push(target);
+        }
+
+        &mut self.targets[depth]
+    }
+
+    ///
+    /// Applies the group item's filter to its target, if it has one.
+    ///
+    pub(crate) fn filter(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        index: usize,
+    ) {
+        let item = &self.items[index];
+
+        if let Some(filter) = item.filter {
+            let target = &self.targets[item.depth];
+
+            self.filter_render.flush(
+                device,
+                &self.layout,
+                encoder,
+                &target.view,
+                &target.bind_group,
+                filter,
+            );
+        }
+    }
+
+    ///
+    /// Composites the group item's target into the render pass, which must
+    /// target the parent view, or the item's image for an image group.
+    ///
+    pub(crate) fn composite<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>, index: usize) {
+        let item = &self.items[index];
+        let target = &self.targets[item.depth];
+
+        match item.image {
+            Some(_) => rpass.set_pipeline(&self.image_pipeline),
+            None => rpass.set_pipeline(&self.pipelines[blend_index(item.blend)]),
+        }
+
+        rpass.set_bind_group(0, &target.bind_group, &[]);
+        rpass.set_vertex_buffer(0, self.style_buffer.slice(..));
+
+        rpass.draw(0..3, index as u32..index as u32 + 1);
+    }
+}
+
+pub(crate) struct GroupTarget {
+    // shared, so the renderer can hold the view while drawing into the canvas
+    view: Arc<wgpu::TextureView>,
+    bind_group: wgpu::BindGroup,
+
+    pub(crate) msaa: Option<MsaaTarget>,
+}
+
+impl GroupTarget {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        let view = color_texture(device, format, width, height, 1)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+            ],
+            label: Some("group_bind_group"),
+        });
+
+        let msaa = if sample_count > 1 {
+            Some(MsaaTarget::new(device, format, width, height, sample_count))
+        } else {
+            None
+        };
+
+        Self {
+            view: Arc::new(view),
+            bind_group,
+            msaa,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn view(&self) -> &Arc<wgpu::TextureView> {
+        &self.view
+    }
+}
+
+struct GroupItem {
+    depth: usize,
+    blend: BlendMode,
+    image: Option<ImageId>,
+    // index of the filter's passes
+    filter: Option<usize>,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct GroupStyle {
+    // opacity, x offset, y offset, y flip
+    opacity: [f32; 4],
+}
+
+impl GroupStyle {
+    const ATTRS: [wgpu::VertexAttribute; 1] =
+        wgpu::vertex_attr_array![0 => Float32x4];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GroupStyle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRS,
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            opacity: [1., 0., 0., 1.],
+        }
+    }
+
+    fn new(opacity: f32, offset: [f32; 2], flip: f32) -> Self {
+        Self {
+            opacity: [opacity, offset[0], offset[1], flip],
+        }
+    }
+}
+
+fn blend_index(blend: BlendMode) -> usize {
+    match blend {
+        BlendMode::Normal => 0,
+        BlendMode::Multiply => 1,
+        BlendMode::Screen => 2,
+        BlendMode::Additive => 3,
+    }
+}
+
+///
+/// Blend state for a premultiplied source. Alpha is always composited over
+/// the destination.
+///
+fn blend_state(blend: BlendMode) -> wgpu::BlendState {
+    let (src_factor, dst_factor) = match blend {
+        BlendMode::Normal => (wgpu::BlendFactor::One, wgpu::BlendFactor::OneMinusSrcAlpha),
+        // src * dst + (1 - src_alpha) * dst
+        BlendMode::Multiply => (wgpu::BlendFactor::Dst, wgpu::BlendFactor::OneMinusSrcAlpha),
+        // src + dst - src * dst
+        BlendMode::Screen => (wgpu::BlendFactor::One, wgpu::BlendFactor::OneMinusSrc),
+        BlendMode::Additive => (wgpu::BlendFactor::One, wgpu::BlendFactor::One),
+    };
+
+    wgpu::BlendState {
+        color: wgpu::BlendComponent {
+            src_factor,
+            dst_factor,
+            operation: wgpu::BlendOperation::Add,
+        },
+        alpha: wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        },
+    }
+}
+
+fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                },
+                count: None,
+            },
+        ],
+        label: Some("group_bind_group_layout"),
+    })
+}
+
+fn create_group_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    blend: BlendMode,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("group.wgsl"));
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[
+            layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_group",
+            buffers: &[
+                GroupStyle::desc(),
+            ],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_group",
+            targets: &[
+                Some(wgpu::ColorTargetState {
+                    format,
+
+                    blend: Some(blend_state(blend)),
+
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            ],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    })
+}
+
+///
+/// Pipeline copying a group target into an image texture, which is drawn
+/// with straight alpha like other images.
+///
+fn create_image_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("group.wgsl"));
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[
+            layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_group",
+            buffers: &[
+                GroupStyle::desc(),
+            ],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_image",
+            targets: &[
+                Some(wgpu::ColorTargetState {
+                    format: IMAGE_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            ],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
@@ -0,0 +1,60 @@
+@group(0) @binding(0)
+var t_group: texture_2d<f32>;
+
+struct GroupStyle {
+    @location(0) opacity: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) opacity: f32,
+    @location(1) offset: vec2<f32>,
+    @location(2) flip: f32,
+};
+
+// single triangle covering the full viewport
+@vertex
+fn vs_group(
+    @builtin(vertex_index) index: u32,
+    style: GroupStyle,
+) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.pos = vec4<f32>(x, y, 0.0, 1.0);
+    out.opacity = style.opacity.x;
+    out.offset = style.opacity.yz;
+    out.flip = style.opacity.w;
+    return out;
+}
+
+// texel in the group target, where images are flipped because image rows
+// are drawn bottom to top
+fn load_group(in: VertexOutput) -> vec4<f32> {
+    let xy = in.offset + vec2<f32>(in.pos.x, in.flip * in.pos.y);
+
+    return textureLoad(t_group, vec2<i32>(xy), 0);
+}
+
+// the group texture is premultiplied, because it's drawn over transparent,
+// so opacity scales all channels
+@fragment
+fn fs_group(
+    in: VertexOutput,
+) -> @location(0) vec4<f32> {
+    return in.opacity * load_group(in);
+}
+
+// images are drawn with straight alpha
+@fragment
+fn fs_image(
+    in: VertexOutput,
+) -> @location(0) vec4<f32> {
+    let color = load_group(in);
+
+    if color.a <= 0.0 {
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+
+    return vec4<f32>(color.rgb / color.a, color.a);
+}
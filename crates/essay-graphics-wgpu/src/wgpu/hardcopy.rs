@@ -1,6 +1,6 @@
 use std::{fs::File, io::BufWriter, ops::Deref};
 
-use essay_graphics_api::renderer::Drawable;
+use essay_graphics_api::{renderer::Drawable, ColorDeficiency};
 use wgpu::BufferView;
 use image::{ImageBuffer, Rgba};
 
@@ -22,6 +22,14 @@ pub struct WgpuHardcopy {
 
 impl WgpuHardcopy {
     pub fn new(width: u32, height: u32) -> WgpuHardcopy {
+        Self::with_sample_count(width, height, 1)
+    }
+
+    ///
+    /// Creates a hardcopy with a multisample (MSAA) count. The hardcopy 
+    /// texture is the resolve target. 
+    ///
+    pub fn with_sample_count(width: u32, height: u32, sample_count: u32) -> WgpuHardcopy {
         let (device, queue) = pollster::block_on(wgpu_device());
 
         let texture_format = wgpu::TextureFormat::Rgba8UnormSrgb;
@@ -51,12 +59,13 @@ impl WgpuHardcopy {
         
         let texture = device.create_texture(&texture_desc);
 
-        let canvas = PlotCanvas::new(
+        let canvas = PlotCanvas::with_sample_count(
             &device,
             &queue,
             texture_format,
             width,
-            height
+            height,
+            sample_count,
         );
     
         Self {
@@ -201,6 +210,28 @@ impl WgpuHardcopy {
         pollster::block_on(self.read_into_async(id, fun))
     }
 
+    ///
+    /// Reads the surface as seen with a color vision deficiency, or
+    /// unchanged for None, to preview a figure for color blind readers.
+    ///
+    pub fn read_simulated<R>(
+        &mut self,
+        id: SurfaceId,
+        deficiency: Option<ColorDeficiency>,
+        fun: impl FnOnce(ImageBuffer::<Rgba<u8>, &[u8]>) -> R
+    ) -> R {
+        let width = self.texture_size.width;
+        let height = self.texture_size.height;
+
+        let mut vec = self.read_into(id, |image| image.as_raw().to_vec());
+
+        if let Some(deficiency) = deficiency {
+            deficiency.apply_rgba(&mut vec);
+        }
+
+        fun(ImageBuffer::from_raw(width, height, vec.deref()).unwrap())
+    }
+
     pub async fn read_into_async<R>(
         &mut self, 
         id: SurfaceId, 
@@ -372,11 +403,14 @@ impl WgpuHardcopy {
             self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
         {
+            // clears the msaa texture when multisampling
+            let (target, resolve_target) = self.canvas.color_target(view);
+
             let _ = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: None,
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    view: target,
+                    resolve_target,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
                             r: 1.0,
@@ -1,8 +1,11 @@
+use std::ops::Range;
+
 use bytemuck_derive::{Pod, Zeroable};
 use essay_graphics_api::{renderer::Canvas, Affine2d, Bounds, ImageId};
 use essay_tensor::Tensor;
 use wgpu::util::DeviceExt;
 
+pub(crate) const IMAGE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
 
 pub struct ImageRender {
     textures: Vec<RgbaTexture>,
@@ -29,6 +32,7 @@ impl ImageRender {
     pub(crate) fn new(
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -69,6 +73,7 @@ impl ImageRender {
         let pipeline = create_image_pipeline(
             device, 
             format,
+            sample_count,
         );
     
         Self {
@@ -94,6 +99,7 @@ impl ImageRender {
     }
 
     pub fn clear(&mut self) {
+        self.image_items.drain(..);
         self.vertex_offset = 0;
         self.style_offset = 0;
 
@@ -115,6 +121,7 @@ impl ImageRender {
             image.clone(),
             colors.dim(1) as u32, 
             colors.dim(0) as u32,
+            false,
         );
 
         let tex_index = self.textures.len();
@@ -128,6 +135,39 @@ impl ImageRender {
         image
     }
 
+    ///
+    /// Creates an image that's written by a render pass instead of from
+    /// tensor data, for offscreen rendering.
+    ///
+    pub(crate) fn create_target(
+        &mut self, 
+        device: &wgpu::Device, 
+        width: u32, 
+        height: u32
+    ) -> ImageId {
+        let image_id = self.image_id;
+        self.image_id += 1;
+
+        let image = ImageId::new(image_id);
+
+        let mut texture = RgbaTexture::new(device, image.clone(), width, height, true);
+        texture.is_stale = false;
+
+        self.textures.push(texture);
+
+        image
+    }
+
+    ///
+    /// Returns a render attachment view for an image created by
+    /// create_target.
+    ///
+    pub(crate) fn target_view(&self, image: &ImageId) -> Option<wgpu::TextureView> {
+        self.textures.iter()
+            .find(|tex| &tex.image_id == image)
+            .map(|tex| tex.texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
     pub(crate) fn _draw(
         &mut self, 
         _device: &wgpu::Device,
@@ -239,15 +279,21 @@ impl ImageRender {
         self.style_offset += 1;
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.image_items.len()
+    }
+
     pub fn flush(
         &mut self, 
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
+        items: Range<usize>,
     ) {
         //self.text_cache.flush(queue, &self.texture);
 
-        if self.image_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -255,7 +301,7 @@ impl ImageRender {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -288,7 +334,7 @@ impl ImageRender {
             );
         }
 
-        for item in self.image_items.drain(..) {
+        for item in &self.image_items[items] {
             rpass.set_pipeline(&self.pipeline);
 
             let stride = self.vertex_stride;
@@ -301,7 +347,7 @@ impl ImageRender {
                 (stride * item.index) as u64..(stride * (item.index + 1)) as u64
             ));
 
-            if let Some(_image) = item.image {
+            if let Some(_image) = &item.image {
                 todo!();
                 //write_rgba_texture(queue, &self.textures[item.tex_index], &image, 
                 //    image.dim(1) as u32, image.dim(0) as u32
@@ -316,8 +362,6 @@ impl ImageRender {
             );
 
         }
-
-        self.vertex_offset = 0;
     }
 
     fn vertex(&mut self, x: f32, y: f32, u: f32, v: f32) {
@@ -421,8 +465,14 @@ pub struct RgbaTexture {
 }
 
 impl RgbaTexture {
-    pub fn new(device: &wgpu::Device, image_id: ImageId, width: u32, height: u32) -> Self {
-        let texture = create_rgba_texture(device, width, height);
+    pub fn new(
+        device: &wgpu::Device, 
+        image_id: ImageId, 
+        width: u32, 
+        height: u32,
+        is_target: bool,
+    ) -> Self {
+        let texture = create_rgba_texture(device, width, height, is_target);
         let layout = create_bind_group_layout(device);
         let bind_group = create_texture_bind_group(device, &layout, &texture);
         //let image_id = Image::new(id);
@@ -464,17 +514,30 @@ fn write_rgba_texture(
     );
 }
 
-fn create_rgba_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+fn create_rgba_texture(
+    device: &wgpu::Device, 
+    width: u32, 
+    height: u32,
+    is_target: bool,
+) -> wgpu::Texture {
+    let usage = if is_target {
+        wgpu::TextureUsages::TEXTURE_BINDING 
+            | wgpu::TextureUsages::COPY_DST
+            | wgpu::TextureUsages::RENDER_ATTACHMENT
+    } else {
+        wgpu::TextureUsages::TEXTURE_BINDING 
+            | wgpu::TextureUsages::COPY_DST
+    };
+
     device.create_texture(
         &wgpu::TextureDescriptor {
             size: texture_size(width, height),
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format: IMAGE_FORMAT,
             //format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING 
-                | wgpu::TextureUsages::COPY_DST,
+            usage,
             label: Some("rgba_texture"),
             view_formats: &[],
         }
@@ -538,6 +601,7 @@ fn create_texture_bind_group(
 fn create_image_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::include_wgsl!("image.wgsl"));
 
@@ -591,7 +655,10 @@ fn create_image_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
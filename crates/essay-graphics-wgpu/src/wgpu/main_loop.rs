@@ -1,10 +1,14 @@
 use std::time::Instant;
 
-use essay_graphics_api::{renderer::{Canvas, DeviceErr, Drawable, Event}, Bounds, Point};
+use essay_graphics_api::{
+    input::{Gesture, GestureRecognizer, Gestures, InputRecorder, RawInput, TouchPhase},
+    renderer::{Canvas, Cursor, DeviceErr, Drawable, Event, Key, KeyState, Modifiers, MouseButton, ScrollDelta},
+    Bounds, Point
+};
 use winit::{
-    event::{self, ElementState, MouseButton, WindowEvent }, 
+    event::{self, ElementState, MouseScrollDelta, WindowEvent }, 
     event_loop::{ControlFlow, EventLoop}, 
-    keyboard::{Key, NamedKey}, 
+    keyboard::{self, NamedKey}, 
     window::{CursorIcon, Window}
 };
 
@@ -14,12 +18,18 @@ use super::render::PlotRenderer;
 
 pub struct WgpuMainLoop {
     title: Option<String>,
+    sample_count: u32,
+    gestures: Gestures,
+    record_path: Option<String>,
 }
 
 impl WgpuMainLoop {
     pub fn new() -> Self {
         Self {
             title: None,
+            sample_count: 1,
+            gestures: Gestures::new(),
+            record_path: None,
         }
     }
 
@@ -29,6 +39,37 @@ impl WgpuMainLoop {
         self
     }
 
+    ///
+    /// Multisample (MSAA) count for the window. 1 disables multisampling,
+    /// and 4 is supported by all adapters.
+    ///
+    pub fn set_sample_count(&mut self, sample_count: u32) -> &mut Self {
+        assert!(sample_count > 0, "sample_count must be positive");
+
+        self.sample_count = sample_count;
+
+        self
+    }
+
+    ///
+    /// Button mapping and thresholds for pan, zoom box and reset gestures.
+    ///
+    pub fn set_gestures(&mut self, gestures: Gestures) -> &mut Self {
+        self.gestures = gestures;
+
+        self
+    }
+
+    ///
+    /// Records the window's input to a file when the window closes, for
+    /// replay with InputRecorder.
+    ///
+    pub fn set_record_path(&mut self, path: &str) -> &mut Self {
+        self.record_path = Some(String::from(path));
+
+        self
+    }
+
     pub fn main_loop(&mut self, drawable: Box<dyn Drawable>) -> Result<(), DeviceErr> {
         let event_loop = EventLoop::new().unwrap();
         let window = winit::window::Window::new(&event_loop).unwrap();
@@ -41,7 +82,15 @@ impl WgpuMainLoop {
 
         let wgpu_device = pollster::block_on(init_wgpu_device(&window));
     
-        run_event_loop(event_loop, window, wgpu_device, drawable);
+        run_event_loop(
+            event_loop,
+            window,
+            wgpu_device,
+            self.sample_count,
+            self.gestures.clone(),
+            self.record_path.clone(),
+            drawable
+        );
 
         Ok(())
     }
@@ -114,6 +163,9 @@ fn run_event_loop(
     event_loop: EventLoop<()>, 
     window: Window, 
     args: MainLoopDevice,
+    sample_count: u32,
+    gestures: Gestures,
+    record_path: Option<String>,
     drawable: Box<dyn Drawable>,
 ) {
     let MainLoopDevice {
@@ -127,24 +179,21 @@ fn run_event_loop(
 
     let mut drawable = drawable;
 
-    let mut canvas = PlotCanvas::new(
+    let mut canvas = PlotCanvas::with_sample_count(
         &device,
         &queue,
         config.format,
         config.width,
         config.height,
+        sample_count,
     );
 
     canvas.set_scale_factor(window.scale_factor() as f32);
 
-    let pan_min = 20.;
-    let zoom_min = 20.;
-
-    // TODO: is double clicking not recommended?
-    let dbl_click = 500; // time in millis
-
-    let mut cursor = CursorState::new();
-    let mut mouse = MouseState::new();
+    let start_time = Instant::now();
+    let mut recognizer = GestureRecognizer::new(gestures);
+    let mut cursor_icon = CursorIcon::Default;
+    let mut recorder = InputRecorder::new();
 
     event_loop.run(move |event, window_target| {
         let _ = (&instance, &adapter, &drawable);
@@ -167,165 +216,93 @@ fn run_event_loop(
                 canvas.resize(&device, size.width, size.height);
                 // canvas.set_scale_factor()
                 canvas.request_redraw(true);
+
+                if record_path.is_some() {
+                    recorder.record(
+                        start_time.elapsed(),
+                        &RawInput::Resize(size.width as f32, size.height as f32)
+                    );
+                }
+
                 let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
                 drawable.event(&mut renderer, &Event::Resize(bounds));
             }
             event::Event::WindowEvent {
-                event: WindowEvent::MouseInput {
-                    state,
-                    button,
-                    ..
-                },
+                event: WindowEvent::RedrawRequested,
                 ..
             } => {
-                let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
-                match button {
-                    MouseButton::Left => {
-                        mouse.left = state;
-
-                        if state == ElementState::Pressed {
-                            drawable.event(
-                                &mut renderer,
-                                &Event::MouseLeftPress(cursor.position),
-                            );
-                            let now = Instant::now();
-
-                            if now.duration_since(mouse.left_press_time).as_millis() < dbl_click {
-                                drawable.event(
-                                    &mut renderer,
-                                    &Event::ResetView(cursor.position),
-                                )
-                            }
-
-                            mouse.left_press_start = cursor.position;
-                            mouse.left_press_last = cursor.position;
-                            mouse.left_press_time = now;
-                            window.set_cursor_icon(CursorIcon::Grab);
-                        } else {
-                            window.set_cursor_icon(CursorIcon::Default);
-                        }
-                    },
-                    MouseButton::Right => {
-                        mouse.right = state;
-
-                        match state {
-                            ElementState::Pressed => {
-                                drawable.event(
-                                    &mut renderer,
-                                    &Event::MouseRightPress(cursor.position),
-                                );
-
-                                mouse.right_press_start = cursor.position;
-                                mouse.right_press_time = Instant::now();
-                                window.set_cursor_icon(CursorIcon::Crosshair);
-                            }
-                            ElementState::Released => {
-                                drawable.event(
-                                    &mut renderer,
-                                    &Event::MouseRightRelease(cursor.position),
-                                );
-
-                                if zoom_min <= mouse.right_press_start.dist(&cursor.position) {
-                                    drawable.event(
-                                        &mut renderer,
-                                        &Event::ZoomBounds(
-                                            mouse.right_press_start, 
-                                            cursor.position
-                                        )
-                                    );
-                                }
-                                window.set_cursor_icon(CursorIcon::Default);
-                            }
-                        }
-                    },
-                    _ => {}
-                }
-            }
+                canvas.request_redraw(true);
+            },
             event::Event::WindowEvent {
-                event: WindowEvent::CursorMoved {
-                    position,
-                    ..
-                },
+                event: WindowEvent::CloseRequested,
                 ..
             } => {
-                cursor.position = Point(position.x as f32, config.height as f32 - position.y as f32);
-                let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
-
-                if mouse.left == ElementState::Pressed 
-                    && pan_min <= mouse.left_press_start.dist(&cursor.position) {
-                    drawable.event(
-                        &mut renderer,
-                        &Event::Pan(
-                            mouse.left_press_start, 
-                            mouse.left_press_last, 
-                            cursor.position
-                        ),
-                    );
-
-                    mouse.left_press_last = cursor.position;
-                }
-                if mouse.right == ElementState::Pressed
-                    && pan_min <= mouse.left_press_start.dist(&cursor.position) {
-                        drawable.event(
-                            &mut renderer,
-                            &Event::MouseRightDrag(mouse.left_press_start, cursor.position),
-                    );
+                if let Some(path) = &record_path {
+                    if let Err(err) = recorder.write(path) {
+                        log::warn!("Failed to write input recording {}", err.0);
+                    }
                 }
+
+                window_target.exit()
             }
             event::Event::WindowEvent {
-                event: WindowEvent::KeyboardInput { event, .. },
+                event,
                 ..
             } => {
-                let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
-                if event.state == ElementState::Pressed {
-                    let pos = Point(0., 0.);
-
-                    match event.logical_key {
-                        Key::Character(key) => {
-                            let ch = key.chars().next().unwrap();
-                            drawable.event(
-                                &mut renderer,
-                                &Event::KeyPress(pos, ch)
-                            );
-                        }
-                        Key::Named(NamedKey::Space) => {
-                            // TODO: replace with KeyPressNamed
-                            drawable.event(
-                                &mut renderer,
-                                &Event::KeyPress(pos, ' ')
-                            );
-                        },
-                        Key::Named(NamedKey::Tab) => {
-                            // TODO: replace with KeyPressNamed
-                            drawable.event(
-                                &mut renderer,
-                                &Event::KeyPress(pos, '\r')
-                            );
-                        },
-                        Key::Named(NamedKey::Enter) => {
-                            // TODO: replace with KeyPressNamed
-                            drawable.event(
-                                &mut renderer,
-                                &Event::KeyPress(pos, '\n')
-                            );
-                        },
-                        Key::Named(_) => {},
-                        Key::Unidentified(_) => {},
-                        Key::Dead(_) => {},
+                if let Some(input) = to_raw_input(&event, config.height) {
+                    let time = start_time.elapsed();
+
+                    if record_path.is_some() {
+                        recorder.record(time, &input);
+                    }
+
+                    // drawables request a cursor for each new hover position
+                    if let RawInput::CursorMoved(_) | RawInput::CursorLeft = input {
+                        canvas.set_cursor(Cursor::Default);
+                    }
+
+                    let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
+
+                    for event in recognizer.input(time, &input) {
+                        drawable.event(&mut renderer, &event);
+                    }
+
+                    // an active gesture overrides the drawable's cursor
+                    let icon = match recognizer.gesture() {
+                        Gesture::None => to_cursor_icon(canvas.cursor()),
+                        Gesture::Pan => CursorIcon::Grab,
+                        Gesture::Zoom => CursorIcon::Crosshair,
+                    };
+
+                    if icon != cursor_icon {
+                        cursor_icon = icon;
+
+                        window.set_cursor_icon(icon);
                     }
                 }
             }
-            event::Event::WindowEvent {
-                event: WindowEvent::RedrawRequested,
-                ..
-            } => {
-                canvas.request_redraw(true);
-            },
-            event::Event::WindowEvent {
-                event: WindowEvent::CloseRequested,
-                ..
-            } => window_target.exit(),
             event::Event::AboutToWait => {
+                // a touch held without input becomes a long press
+                if let Some(deadline) = recognizer.deadline() {
+                    let time = start_time.elapsed();
+
+                    if deadline <= time {
+                        if record_path.is_some() {
+                            recorder.record(time, &RawInput::Tick);
+                        }
+
+                        let mut renderer = PlotRenderer::new(&mut canvas, &device, Some(&queue), None);
+
+                        for event in recognizer.input(time, &RawInput::Tick) {
+                            drawable.event(&mut renderer, &event);
+                        }
+                    }
+                }
+
+                if let Some(deadline) = recognizer.deadline() {
+                    window_target.set_control_flow(ControlFlow::WaitUntil(start_time + deadline));
+                }
+
                 if canvas.is_request_redraw() {
                     canvas.request_redraw(false);
 
@@ -337,41 +314,159 @@ fn run_event_loop(
     }).unwrap();
 }
 
-struct MouseState {
-    left: ElementState,
-    left_press_start: Point,
-    left_press_last: Point,
-    left_press_time: Instant,
-
-    right: ElementState,
-    right_press_start: Point,
-    right_press_time: Instant,
+fn to_cursor_icon(cursor: Cursor) -> CursorIcon {
+    match cursor {
+        Cursor::Default => CursorIcon::Default,
+        Cursor::Pointer => CursorIcon::Pointer,
+        Cursor::Text => CursorIcon::Text,
+        Cursor::Crosshair => CursorIcon::Crosshair,
+        Cursor::Move => CursorIcon::Move,
+        Cursor::Grab => CursorIcon::Grab,
+        Cursor::Grabbing => CursorIcon::Grabbing,
+        Cursor::ResizeHorizontal => CursorIcon::EwResize,
+        Cursor::ResizeVertical => CursorIcon::NsResize,
+        Cursor::NotAllowed => CursorIcon::NotAllowed,
+        Cursor::Wait => CursorIcon::Wait,
+    }
 }
 
-impl MouseState {
-    fn new() -> Self {
-        Self {
-            left: ElementState::Released,
-            left_press_start: Point(0., 0.),
-            left_press_last: Point(0., 0.),
-            left_press_time: Instant::now(),
-
-            right: ElementState::Released,
-            right_press_start: Point(0., 0.),
-            right_press_time: Instant::now(),
+///
+/// Raw input for a window event, where height flips the cursor y so the
+/// origin is the lower left.
+///
+fn to_raw_input(event: &WindowEvent, height: u32) -> Option<RawInput> {
+    match event {
+        WindowEvent::CursorMoved { position, .. } => {
+            Some(RawInput::CursorMoved(
+                Point(position.x as f32, height as f32 - position.y as f32)
+            ))
+        }
+        WindowEvent::CursorLeft { .. } => Some(RawInput::CursorLeft),
+        WindowEvent::MouseInput { state, button, .. } => {
+            let button = match button {
+                event::MouseButton::Left => MouseButton::Left,
+                event::MouseButton::Right => MouseButton::Right,
+                event::MouseButton::Middle => MouseButton::Middle,
+                event::MouseButton::Back => MouseButton::Back,
+                event::MouseButton::Forward => MouseButton::Forward,
+                event::MouseButton::Other(id) => MouseButton::Other(*id),
+            };
+
+            match state {
+                ElementState::Pressed => Some(RawInput::ButtonPress(button)),
+                ElementState::Released => Some(RawInput::ButtonRelease(button)),
+            }
+        }
+        WindowEvent::MouseWheel { delta, .. } => {
+            Some(RawInput::Wheel(match delta {
+                MouseScrollDelta::LineDelta(x, y) => ScrollDelta::Line(*x, *y),
+                MouseScrollDelta::PixelDelta(pos) => {
+                    ScrollDelta::Pixel(pos.x as f32, pos.y as f32)
+                }
+            }))
         }
+        WindowEvent::TouchpadMagnify { delta, .. } => {
+            Some(RawInput::Magnify(*delta as f32))
+        }
+        WindowEvent::TouchpadRotate { delta, .. } => {
+            Some(RawInput::Rotate(delta.to_radians()))
+        }
+        WindowEvent::Touch(touch) => {
+            let phase = match touch.phase {
+                event::TouchPhase::Started => TouchPhase::Start,
+                event::TouchPhase::Moved => TouchPhase::Move,
+                event::TouchPhase::Ended => TouchPhase::End,
+                event::TouchPhase::Cancelled => TouchPhase::Cancel,
+            };
+
+            Some(RawInput::Touch(
+                touch.id,
+                phase,
+                Point(touch.location.x as f32, height as f32 - touch.location.y as f32)
+            ))
+        }
+        WindowEvent::ModifiersChanged(state) => {
+            let state = state.state();
+
+            Some(RawInput::Modifiers(Modifiers {
+                shift: state.shift_key(),
+                ctrl: state.control_key(),
+                alt: state.alt_key(),
+                super_key: state.super_key(),
+            }))
+        }
+        WindowEvent::KeyboardInput { event, .. } => {
+            let state = match (event.state, event.repeat) {
+                (ElementState::Released, _) => KeyState::Release,
+                (ElementState::Pressed, false) => KeyState::Press,
+                (ElementState::Pressed, true) => KeyState::Repeat,
+            };
+
+            Some(RawInput::Key(to_key(&event.logical_key), state))
+        }
+        _ => None,
     }
 }
 
-struct CursorState {
-    position: Point,
-}
-
-impl CursorState {
-    fn new() -> Self {
-        Self {
-            position: Point(0., 0.),
-        }
+fn to_key(key: &keyboard::Key) -> Key {
+    match key {
+        keyboard::Key::Character(text) => match text.chars().next() {
+            Some(ch) => Key::Char(ch),
+            None => Key::Other,
+        },
+        keyboard::Key::Named(named) => match named {
+            NamedKey::Enter => Key::Enter,
+            NamedKey::Tab => Key::Tab,
+            NamedKey::Space => Key::Space,
+            NamedKey::Backspace => Key::Backspace,
+            NamedKey::Delete => Key::Delete,
+            NamedKey::Escape => Key::Escape,
+            NamedKey::Insert => Key::Insert,
+
+            NamedKey::ArrowLeft => Key::ArrowLeft,
+            NamedKey::ArrowRight => Key::ArrowRight,
+            NamedKey::ArrowUp => Key::ArrowUp,
+            NamedKey::ArrowDown => Key::ArrowDown,
+
+            NamedKey::Home => Key::Home,
+            NamedKey::End => Key::End,
+            NamedKey::PageUp => Key::PageUp,
+            NamedKey::PageDown => Key::PageDown,
+
+            NamedKey::F1 => Key::F(1),
+            NamedKey::F2 => Key::F(2),
+            NamedKey::F3 => Key::F(3),
+            NamedKey::F4 => Key::F(4),
+            NamedKey::F5 => Key::F(5),
+            NamedKey::F6 => Key::F(6),
+            NamedKey::F7 => Key::F(7),
+            NamedKey::F8 => Key::F(8),
+            NamedKey::F9 => Key::F(9),
+            NamedKey::F10 => Key::F(10),
+            NamedKey::F11 => Key::F(11),
+            NamedKey::F12 => Key::F(12),
+            NamedKey::F13 => Key::F(13),
+            NamedKey::F14 => Key::F(14),
+            NamedKey::F15 => Key::F(15),
+            NamedKey::F16 => Key::F(16),
+            NamedKey::F17 => Key::F(17),
+            NamedKey::F18 => Key::F(18),
+            NamedKey::F19 => Key::F(19),
+            NamedKey::F20 => Key::F(20),
+            NamedKey::F21 => Key::F(21),
+            NamedKey::F22 => Key::F(22),
+            NamedKey::F23 => Key::F(23),
+            NamedKey::F24 => Key::F(24),
+
+            NamedKey::Shift => Key::Shift,
+            NamedKey::Control => Key::Control,
+            NamedKey::Alt => Key::Alt,
+            NamedKey::Super => Key::Super,
+
+            _ => Key::Other,
+        },
+        keyboard::Key::Unidentified(_) => Key::Other,
+        keyboard::Key::Dead(_) => Key::Other,
     }
 }
 
@@ -393,11 +488,14 @@ fn main_render(
         device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
     {
+        // clears the msaa texture when multisampling
+        let (target, resolve_target) = canvas.color_target(&view);
+
         let _ = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
+                view: target,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color {
                         r: 1.0,
@@ -3,12 +3,17 @@ mod bezier;
 mod image;
 mod main_loop;
 mod render;
+mod scalar_image;
 mod shape2d;
 mod shape2d_texture;
 mod shape2d_tex2;
 mod triangle2d;
 mod form3d;
+mod filter;
+mod gradient;
+mod group;
 mod triangulate;
+mod msaa;
 mod text;
 mod text_texture;
 mod text_cache;
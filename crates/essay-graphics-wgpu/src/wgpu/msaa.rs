@@ -0,0 +1,208 @@
+///
+/// Multisampled color target for the canvas. Render passes draw into the
+/// multisampled texture and resolve into the surface or hardcopy texture.
+/// 
+/// Paths drawn without antialiasing are rendered into a single-sample 
+/// alias texture and then composited into the multisampled texture, because
+/// a multisampled pipeline can't disable coverage per draw.
+///
+pub(crate) struct MsaaTarget {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+
+    view: wgpu::TextureView,
+    alias_view: wgpu::TextureView,
+    alias_bind_group: wgpu::BindGroup,
+
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl MsaaTarget {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        sample_count: u32,
+    ) -> Self {
+        assert!(sample_count > 1, "msaa sample_count must be larger than 1 {}", sample_count);
+
+        let view = color_texture(device, format, width, height, sample_count)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let alias_view = color_texture(device, format, width, height, 1)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let layout = create_bind_group_layout(device);
+        let alias_bind_group = create_bind_group(device, &layout, &alias_view);
+
+        let pipeline = create_composite_pipeline(device, &layout, format, sample_count);
+
+        Self {
+            format,
+            sample_count,
+            view,
+            alias_view,
+            alias_bind_group,
+            layout,
+            pipeline,
+        }
+    }
+
+    pub(crate) fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let format = self.format;
+
+        self.view = color_texture(device, format, width, height, self.sample_count)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.alias_view = color_texture(device, format, width, height, 1)
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        self.alias_bind_group = create_bind_group(device, &self.layout, &self.alias_view);
+    }
+
+    #[inline]
+    pub(crate) fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    #[inline]
+    pub(crate) fn alias_view(&self) -> &wgpu::TextureView {
+        &self.alias_view
+    }
+
+    ///
+    /// Blends the alias texture over the multisampled target. The render pass
+    /// must target the multisampled view.
+    ///
+    pub(crate) fn composite<'a>(&'a self, rpass: &mut wgpu::RenderPass<'a>) {
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &self.alias_bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+///
+/// Returns the render pass attachment and resolve target for a view,
+/// drawing into the multisampled texture when msaa is enabled.
+///
+pub(crate) fn color_target<'a>(
+    msaa: &'a Option<MsaaTarget>,
+    view: &'a wgpu::TextureView,
+) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+    match msaa {
+        Some(msaa) => (msaa.view(), Some(view)),
+        None => (view, None),
+    }
+}
+
+pub(crate) fn color_texture(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    sample_count: u32,
+) -> wgpu::Texture {
+    device.create_texture(
+        &wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+            label: Some("msaa_texture"),
+            view_formats: &[],
+        }
+    )
+}
+
+fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                },
+                count: None,
+            },
+        ],
+        label: Some("msaa_bind_group_layout"),
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    device.create_bind_group(
+        &wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+            ],
+            label: Some("msaa_bind_group")
+        }
+    )
+}
+
+fn create_composite_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("msaa.wgsl"));
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[
+            layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_composite",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_composite",
+            targets: &[
+                Some(wgpu::ColorTargetState {
+                    format,
+
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            ],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    })
+}
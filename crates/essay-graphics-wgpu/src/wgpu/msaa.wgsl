@@ -0,0 +1,26 @@
+@group(0) @binding(0)
+var t_alias: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+};
+
+// single triangle covering the full viewport
+@vertex
+fn vs_composite(
+    @builtin(vertex_index) index: u32,
+) -> VertexOutput {
+    let x = f32((index << 1u) & 2u) * 2.0 - 1.0;
+    let y = f32(index & 2u) * 2.0 - 1.0;
+    var out: VertexOutput;
+    out.pos = vec4<f32>(x, y, 0.0, 1.0);
+    return out;
+}
+
+// the alias texture is premultiplied, because it's blended over transparent
+@fragment
+fn fs_composite(
+    in: VertexOutput,
+) -> @location(0) vec4<f32> {
+    return textureLoad(t_alias, vec2<i32>(in.pos.xy), 0);
+}
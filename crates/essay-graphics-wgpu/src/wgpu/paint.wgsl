@@ -0,0 +1,50 @@
+// Gradient paint shared by the shape2d and bezier shaders, which the
+// pipelines prepend to each shader's source.
+
+const LUT_WIDTH: u32 = 256u;
+
+@group(0) @binding(0)
+var t_lut: texture_2d<f32>;
+
+// gradient paint from the lookup table, or the solid color for kind 0
+fn paint(
+    color: vec4<f32>, 
+    pos: vec2<f32>, 
+    geom: vec4<f32>, 
+    mode: vec4<f32>
+) -> vec4<f32> {
+    let kind = u32(mode[0]);
+
+    if kind == 0u {
+        return color;
+    }
+
+    var t: f32;
+
+    if kind == 1u {
+        let d = geom.zw - geom.xy;
+        t = dot(pos - geom.xy, d) / max(dot(d, d), 1e-12);
+    } else {
+        t = length(pos - geom.xy) / geom.z;
+    }
+
+    let spread = u32(mode[1]);
+
+    if spread == 0u {
+        t = clamp(t, 0.0, 1.0);
+    } else if spread == 1u {
+        t = fract(t);
+    } else {
+        t = 1.0 - abs(t - 2.0 * floor(0.5 * t) - 1.0);
+    }
+
+    let x = t * f32(LUT_WIDTH - 1u);
+    let i0 = min(u32(x), LUT_WIDTH - 1u);
+    let i1 = min(i0 + 1u, LUT_WIDTH - 1u);
+    let row = u32(mode[2]);
+
+    let c0 = textureLoad(t_lut, vec2<u32>(i0, row), 0);
+    let c1 = textureLoad(t_lut, vec2<u32>(i1, row), 0);
+
+    return mix(c0, c1, fract(x)) * color;
+}
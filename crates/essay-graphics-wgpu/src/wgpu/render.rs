@@ -1,11 +1,11 @@
-use std::mem;
+use std::{mem, sync::Arc};
 
 use essay_graphics_api::{
-    form::{Form, FormId, Matrix4, Shape, ShapeId}, renderer::{Canvas, Drawable, RenderErr, Renderer, Result}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, TextStyle, TextureId
+    form::{Form, FormId, Matrix4, Shape, ShapeId}, renderer::{Canvas, Cursor, Drawable, Group, Layer, RenderErr, Renderer, Result}, Affine2d, Bounds, FontStyle, FontTypeId, ImageId, Path, PathOpt, Point, ScalarMap, TextStyle, TextureId
 };
 use essay_tensor::Tensor;
 
-use super::canvas::PlotCanvas;
+use super::{canvas::{DrawBatch, DrawKind, PlotCanvas}, msaa::color_target};
 
 pub struct PlotRenderer<'a> {
     canvas: &'a mut PlotCanvas,
@@ -25,13 +25,19 @@ impl<'a> PlotRenderer<'a> {
     ) -> Self {
         let pos = canvas.bounds().clone();
 
-        Self {
+        let mut renderer = Self {
             device,
             canvas,
             queue,
             view,
             pos,
-        }
+        };
+
+        let scissor = renderer.get_scissor();
+        renderer.canvas.set_layer(Layer::DEFAULT);
+        renderer.canvas.set_scissor(scissor);
+
+        renderer
     }
 
     fn flush_inner(&mut self) {
@@ -40,39 +46,161 @@ impl<'a> PlotRenderer<'a> {
                 let mut encoder =
                    self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
 
-                //let scissor = self.canvas.to_scissor(clip);
-                let scissor = self.get_scissor();
+                // batches are flushed in draw order to preserve painter's order,
+                // with a stable sort so layers are drawn from bottom to top
+                let mut batches = self.canvas.batches().clone();
+                batches.sort_by_key(|batch| batch.layer);
+
+                // path buffers hold every batch's items, so they're
+                // uploaded once per flush rather than once per batch
+                self.canvas.shape2d_render.prepare(self.device, queue);
+                self.canvas.bezier_render.prepare(self.device, queue);
+                self.canvas.gradient_lut.prepare(self.device, queue);
+                self.canvas.group_render.prepare(self.device, queue);
+                self.canvas.scalar_image_render.prepare(self.device, queue);
+
+                // offscreen views of the open groups, innermost last
+                let mut group_views = Vec::<Arc<wgpu::TextureView>>::new();
+
+                for batch in batches {
+                    let is_camera = ! batch.kind.is_mergeable();
+
+                    match batch.kind {
+                        DrawKind::GroupBegin => {
+                            let group_view = self.canvas.flush_group_begin(
+                                self.device, &mut encoder, batch.items.start
+                            );
+
+                            group_views.push(group_view);
+                        }
+                        DrawKind::GroupEnd => {
+                            group_views.pop();
+
+                            let parent = group_views.last().map(|v| v.as_ref()).unwrap_or(view);
+
+                            self.canvas.flush_group_end(
+                                self.device, parent, &mut encoder, batch.scissor, batch.items.start
+                            );
+                        }
+                        _ => {
+                            let target = group_views.last().map(|v| v.as_ref()).unwrap_or(view);
+
+                            self.flush_batch(queue, target, &mut encoder, batch);
+                        }
+                    }
+
+                    if is_camera {
+                        // camera uniforms are single buffers, so each
+                        // camera write needs its own submit
+                        queue.submit(Some(encoder.finish()));
+
+                        encoder = self.device.create_command_encoder(
+                            &wgpu::CommandEncoderDescriptor { label: None }
+                        );
+                    }
+                }
+                
+                queue.submit(Some(encoder.finish()));
+
+                self.canvas.clear();
+            }
+        }
+    }
+
+    fn flush_batch(
+        &mut self,
+        queue: &wgpu::Queue,
+        view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        batch: DrawBatch,
+    ) {
+        let canvas = &mut *self.canvas;
+        let scissor = batch.scissor;
+        let items = batch.items;
 
-                self.canvas.image_render.flush(queue, view, &mut encoder);
-                self.canvas.triangle_render.flush(self.device, queue, view, &mut encoder, scissor);
-                // TODO: order issues with bezier and shape2d
-                self.canvas.shape2d_render.flush(self.device, queue, view, &mut encoder, scissor);
-                self.canvas.bezier_render.flush(self.device, queue, view, &mut encoder, scissor);
-                self.canvas.shape2d_texture_render.flush(self.device, queue, view, &mut encoder, scissor);
-                self.canvas.text_render.flush(queue, view, &mut encoder);
+        // with msaa, passes draw into the multisample texture and resolve into view
+        let (target, resolve) = color_target(&canvas.msaa, view);
 
-                self.canvas.form3d_render.flush(
+        match batch.kind {
+            DrawKind::Path => {
+                canvas.flush_paths(view, encoder, scissor, items, false);
+            }
+            DrawKind::AliasPath => {
+                canvas.flush_paths(view, encoder, scissor, items, true);
+            }
+            DrawKind::Texture => {
+                canvas.shape2d_texture_render.flush(
+                    self.device, queue, target, resolve, encoder, scissor, items
+                );
+            }
+            DrawKind::Text => {
+                canvas.text_render.flush(queue, target, resolve, encoder, items);
+            }
+            DrawKind::Triangles => {
+                canvas.triangle_render.flush(
+                    self.device, queue, target, resolve, encoder, scissor, items
+                );
+            }
+            DrawKind::Image => {
+                canvas.image_render.flush(queue, target, resolve, encoder, items);
+            }
+            DrawKind::ScalarImage => {
+                canvas.scalar_image_render.flush(
+                    target, resolve, encoder, scissor, items
+                );
+            }
+            DrawKind::Form3d => {
+                canvas.form3d_render.flush(
                     self.device, 
                     queue, 
-                    view, 
-                    &mut encoder, 
-                    &self.canvas.texture_store, 
-                    scissor
+                    target, 
+                    resolve,
+                    encoder, 
+                    &canvas.texture_store, 
+                    scissor,
+                    items,
                 );
-                self.canvas.shape2d_tex2_render.flush(
+            }
+            DrawKind::Shape => {
+                canvas.shape2d_tex2_render.flush(
                     self.device, 
                     queue, 
-                    view, 
-                    &mut encoder, 
-                    &self.canvas.texture_store, 
-                    scissor
+                    target, 
+                    resolve,
+                    encoder, 
+                    &canvas.texture_store, 
+                    scissor,
+                    items,
                 );
-                
-                queue.submit(Some(encoder.finish()));
+            }
+            DrawKind::GroupBegin | DrawKind::GroupEnd => {
+                // group targets are switched by flush_inner
             }
         }
     }
 
+    // filtered paths and markers are drawn as a group, so the filter has
+    // a target
+    fn draw_filtered(
+        &mut self,
+        style: &dyn PathOpt,
+        draw: impl FnOnce(&mut PlotCanvas) -> Result<()>,
+    ) -> Result<()> {
+        match style.get_filter() {
+            Some(filter) => {
+                let mut group = Group::new();
+                group.filter(*filter);
+
+                let index = self.canvas.begin_group(&group);
+                let result = draw(&mut *self.canvas);
+                self.canvas.end_group(index);
+
+                result
+            }
+            None => draw(&mut *self.canvas),
+        }
+    }
+
     fn get_scissor(&self) -> Option<(u32, u32, u32, u32)> {
         let pos = &self.pos;
 
@@ -116,7 +244,11 @@ impl<'a> Renderer for PlotRenderer<'a> {
         path: &Path<Canvas>, 
         style: &dyn PathOpt, 
     ) -> Result<(), RenderErr> {
-        self.canvas.draw_path(path, style)
+        if let Some(queue) = self.queue {
+            self.canvas.prepare_hatch(self.device, queue, style);
+        }
+
+        self.draw_filtered(style, |canvas| canvas.draw_path(path, style))
     }
 
     fn draw_markers(
@@ -127,7 +259,7 @@ impl<'a> Renderer for PlotRenderer<'a> {
         color: &Tensor<u32>,
         style: &dyn PathOpt, 
     ) -> Result<(), RenderErr> {
-        self.canvas.draw_markers(marker, xy, scale, color, style)
+        self.draw_filtered(style, |canvas| canvas.draw_markers(marker, xy, scale, color, style))
     }
 
     fn font(
@@ -194,6 +326,13 @@ impl<'a> Renderer for PlotRenderer<'a> {
         self.canvas.request_redraw(true)
     }
 
+    fn set_cursor(
+        &mut self,
+        cursor: Cursor
+    ) {
+        self.canvas.set_cursor(cursor)
+    }
+
     fn draw_image(
         &mut self,
         bounds: &Bounds<Canvas>,
@@ -204,6 +343,15 @@ impl<'a> Renderer for PlotRenderer<'a> {
         self.canvas.draw_image_ref(self.device, bounds, image)
     }
 
+    fn draw_scalar_image(
+        &mut self,
+        bounds: &Bounds<Canvas>,
+        values: &Tensor<f32>,  // [rows, cols]
+        map: &ScalarMap,
+    ) -> Result<(), RenderErr> {
+        self.canvas.draw_scalar_image(self.device, bounds, values, map)
+    }
+
     fn create_image(
         &mut self,
         colors: &Tensor<u8>, // [rows, cols, 4]
@@ -236,7 +384,11 @@ impl<'a> Renderer for PlotRenderer<'a> {
     fn flush(
         &mut self,
     ) {
-        self.flush_inner();
+        // an open group's batches are flushed with its end, since the
+        // group target is composited by the matching GroupEnd
+        if ! self.canvas.is_group_open() {
+            self.flush_inner();
+        }
     }
 
     fn draw_with(
@@ -244,14 +396,58 @@ impl<'a> Renderer for PlotRenderer<'a> {
         pos: &Bounds<Canvas>, 
         drawable: &mut dyn Drawable
     ) -> Result<()> {
-        let push = Push::new(self, pos);
+        let layer = self.canvas.layer();
+
+        self.draw_with_layer(pos, layer, drawable)
+    }
+
+    fn draw_with_layer(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        layer: Layer,
+        drawable: &mut dyn Drawable
+    ) -> Result<()> {
+        let push = Push::new(self, pos, layer);
+
+        // batches are flushed when the top renderer flushes, which lets
+        // later draws in a lower layer appear below this view
+        drawable.draw(push.ptr)
+    }
+
+    fn draw_group(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        group: &Group,
+        drawable: &mut dyn Drawable
+    ) -> Result<()> {
+        let layer = self.canvas.layer();
+        let push = Push::new(self, pos, layer);
+
+        // the group is composited with the view's scissor
+        let index = push.ptr.canvas.begin_group(group);
+
+        let result = drawable.draw(push.ptr);
+
+        push.ptr.canvas.end_group(index);
+
+        result
+    }
+
+    fn draw_to_image(
+        &mut self, 
+        pos: &Bounds<Canvas>, 
+        drawable: &mut dyn Drawable
+    ) -> Result<ImageId> {
+        let layer = self.canvas.layer();
+        let push = Push::new(self, pos, layer);
 
-        drawable.draw(push.ptr)?;
+        let (image, index) = push.ptr.canvas.begin_image(push.ptr.device, pos);
 
-        //push.ptr.flush_inner(&push.clip);
-        push.ptr.flush_inner();
+        let result = drawable.draw(push.ptr);
 
-        Ok(())
+        push.ptr.canvas.end_group(index);
+
+        result.map(|_| image)
     }
 }
 
@@ -259,17 +455,25 @@ struct Push<'a, 'b> {
     ptr: &'a mut PlotRenderer<'b>,
 
     pos: Bounds<Canvas>,
+    layer: Layer,
+    scissor: Option<(u32, u32, u32, u32)>,
 }
 
 impl<'a, 'b> Push<'a, 'b> {
-    fn new(renderer: &'a mut PlotRenderer<'b>, pos: &Bounds<Canvas>) -> Self {
+    fn new(renderer: &'a mut PlotRenderer<'b>, pos: &Bounds<Canvas>, layer: Layer) -> Self {
         let mut push = Self {
+            layer: renderer.canvas.layer(),
+            scissor: renderer.canvas.scissor(),
             ptr: renderer,
             pos: pos.clone(),
         };
 
         mem::swap(&mut push.pos, &mut push.ptr.pos);
 
+        let scissor = push.ptr.get_scissor();
+        push.ptr.canvas.set_layer(layer);
+        push.ptr.canvas.set_scissor(scissor);
+
         push
     }
 } 
@@ -277,6 +481,9 @@ impl<'a, 'b> Push<'a, 'b> {
 impl Drop for Push<'_, '_> {
     fn drop(&mut self) {
         mem::swap(&mut self.pos, &mut self.ptr.pos);
+
+        self.ptr.canvas.set_layer(self.layer);
+        self.ptr.canvas.set_scissor(self.scissor);
     }
 }
 
@@ -285,3 +492,219 @@ impl Drop for PlotRenderer<'_> {
         self.flush_inner();
     }
 }
+
+// The hardcopy tests need a GPU adapter, so they're ignored by default. Run
+// them with `cargo test -- --ignored` on a machine that has one.
+#[cfg(test)]
+mod test {
+    use essay_graphics_api::{
+        renderer::{Canvas, Group, RenderErr, Renderer, Result}, 
+        Bounds, Color, ColorDeficiency, Filter, Path, PathCode, PathStyleBase, Point, ScalarMap
+    };
+    use essay_tensor::Tensor;
+
+    use crate::WgpuHardcopy;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn flush_in_group() {
+        let mut hardcopy = WgpuHardcopy::new(64, 32);
+        let id = hardcopy.add_surface();
+
+        let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+            let pos = Bounds::<Canvas>::from([64., 32.]);
+
+            r.draw_group(&pos, &Group::new(), &mut |r: &mut dyn Renderer| -> Result<()> {
+                let mut red = PathStyleBase::new();
+                red.face_color(0xff0000);
+
+                let mut blue = PathStyleBase::new();
+                blue.face_color(0x0000ff);
+
+                r.draw_path(&rect(0., 32.), &red)?;
+                r.flush();
+                r.draw_path(&rect(32., 64.), &blue)
+            })
+        };
+
+        let (left, right) = hardcopy.draw_and_read(id, &mut drawable, |image| {
+            (*image.get_pixel(16, 16), *image.get_pixel(48, 16))
+        });
+
+        assert_eq!([left.0[0], left.0[2]], [0xff, 0x00]);
+        assert_eq!([right.0[0], right.0[2]], [0x00, 0xff]);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn filter_matches_cpu() {
+        let filter = Filter::drop_shadow([2., -2.], 1., Color(0x000000ff));
+
+        let mut hardcopy = WgpuHardcopy::new(32, 32);
+        let id = hardcopy.add_surface();
+
+        let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+            let pos = Bounds::<Canvas>::from([32., 32.]);
+
+            let mut group = Group::new();
+            group.filter(filter);
+
+            r.draw_group(&pos, &group, &mut |r: &mut dyn Renderer| -> Result<()> {
+                let mut red = PathStyleBase::new();
+                red.face_color(0xff0000);
+
+                r.draw_path(&square(8., 16., 8.), &red)
+            })
+        };
+
+        let gpu = hardcopy.draw_and_read(id, &mut drawable, |image| image.as_raw().to_vec());
+
+        // the same square on a transparent buffer, where canvas y from 16
+        // to 24 is rows 8 to 16
+        let mut cpu = vec![0u8; 4 * 32 * 32];
+
+        for y in 8..16 {
+            for x in 8..16 {
+                let i = 4 * (y * 32 + x);
+
+                cpu[i..i + 4].copy_from_slice(&[0xff, 0x00, 0x00, 0xff]);
+            }
+        }
+
+        filter.apply_rgba(&mut cpu, 32, 32, 1.);
+
+        for (i, (g, c)) in gpu.chunks_exact(4).zip(cpu.chunks_exact(4)).enumerate() {
+            let alpha = c[3] as f32 / 255.;
+
+            for k in 0..3 {
+                // the cpu result over the hardcopy's white background
+                let lrgb = Color::srgb_to_lrgb(c[k] as f32 / 255.) * alpha + 1. - alpha;
+                let expect = (Color::lrgb_to_srgb(lrgb) * 255.).round();
+
+                assert!(
+                    (g[k] as f32 - expect).abs() <= 4.,
+                    "pixel ({}, {}) gpu {:?} cpu {:?}", i % 32, i / 32, g, c
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn read_simulated() {
+        let mut hardcopy = WgpuHardcopy::new(32, 32);
+        let id = hardcopy.add_surface();
+
+        let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+            let mut red = PathStyleBase::new();
+            red.face_color(0xff0000);
+
+            r.draw_path(&rect(0., 32.), &red)
+        };
+
+        hardcopy.draw(&mut drawable);
+        hardcopy.copy_into_buffer(id);
+
+        let pixel = hardcopy.read_simulated(id, None, |image| *image.get_pixel(16, 16));
+        assert_eq!(pixel.0, [0xff, 0x00, 0x00, 0xff]);
+
+        let deficiency = ColorDeficiency::Protanopia;
+        let pixel = hardcopy.read_simulated(id, Some(deficiency), |image| *image.get_pixel(16, 16));
+        let expect = deficiency.simulate(Color(0xff0000ff));
+
+        // simulate truncates to 8 bits, where apply_rgba rounds
+        for (v, e) in pixel.0.iter().zip([expect.r8(), expect.g8(), expect.b8(), 0xff]) {
+            assert!((*v as i32 - e as i32).abs() <= 1, "{:?} {:?}", pixel, expect);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn scalar_image_reuse() {
+        let mut hardcopy = WgpuHardcopy::new(32, 32);
+        let id = hardcopy.add_surface();
+
+        let map = ScalarMap::new([Color(0xff0000ff), Color(0x0000ffff)]);
+
+        // the second frame reuses the first frame's textures
+        for values in [[0f32, 1.], [1., 0.]] {
+            let field = Tensor::from(values.to_vec()).reshape([1, 2]);
+
+            let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+                r.draw_scalar_image(&Bounds::from([32., 32.]), &field, &map)
+            };
+
+            let (left, right) = hardcopy.draw_and_read(id, &mut drawable, |image| {
+                (*image.get_pixel(8, 16), *image.get_pixel(24, 16))
+            });
+
+            let (left_red, right_red) = if values[0] == 0. { (0xff, 0x00) } else { (0x00, 0xff) };
+
+            assert_eq!([left.0[0], right.0[0]], [left_red, right_red]);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn scalar_image_too_large() {
+        let mut hardcopy = WgpuHardcopy::new(32, 32);
+        let id = hardcopy.add_surface();
+
+        let map = ScalarMap::new([Color(0xff0000ff)]);
+
+        // wider than any adapter's 2D texture limit
+        let field = Tensor::from(vec![0f32; 1 << 20]).reshape([1, 1 << 20]);
+
+        let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+            let err = r.draw_scalar_image(&Bounds::from([32., 32.]), &field, &map);
+
+            assert!(matches!(err, Err(RenderErr::TooLarge)));
+
+            Ok(())
+        };
+
+        hardcopy.draw_and_read(id, &mut drawable, |_| ());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn scalar_image_invalid_shape() {
+        let mut hardcopy = WgpuHardcopy::new(32, 32);
+        let id = hardcopy.add_surface();
+
+        let map = ScalarMap::new([Color(0xff0000ff)]);
+
+        let flat = Tensor::from(vec![0f32, 1.]);
+        let empty = Tensor::from(Vec::<f32>::new()).reshape([0, 2]);
+
+        let mut drawable = |r: &mut dyn Renderer| -> Result<()> {
+            for field in [&flat, &empty] {
+                let err = r.draw_scalar_image(&Bounds::from([32., 32.]), field, &map);
+
+                assert!(matches!(err, Err(RenderErr::InvalidShape)));
+            }
+
+            Ok(())
+        };
+
+        hardcopy.draw_and_read(id, &mut drawable, |_| ());
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Path<Canvas> {
+        Path::new(vec![
+            PathCode::MoveTo(Point(x, y)),
+            PathCode::LineTo(Point(x + size, y)),
+            PathCode::LineTo(Point(x + size, y + size)),
+            PathCode::ClosePoly(Point(x, y + size)),
+        ])
+    }
+
+    fn rect(x0: f32, x1: f32) -> Path<Canvas> {
+        Path::new(vec![
+            PathCode::MoveTo(Point(x0, 0.)),
+            PathCode::LineTo(Point(x1, 0.)),
+            PathCode::LineTo(Point(x1, 32.)),
+            PathCode::ClosePoly(Point(x0, 32.)),
+        ])
+    }
+}
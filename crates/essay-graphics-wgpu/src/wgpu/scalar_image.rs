@@ -0,0 +1,632 @@
+use std::ops::Range;
+
+use bytemuck_derive::{Pod, Zeroable};
+use essay_graphics_api::{renderer::{Canvas, RenderErr}, Affine2d, Bounds, ScalarMap};
+use essay_tensor::Tensor;
+use wgpu::util::DeviceExt;
+
+///
+/// Scalar-field images, where the fragment shader maps each value to a
+/// color through a 1D lookup texture. Values and lookup tables are written
+/// in prepare at flush, because draws don't have the queue.
+///
+/// Textures are pooled by shape and reused across frames, so an animated
+/// field of a fixed size only uploads its values.
+///
+pub struct ScalarImageRender {
+    vertex_stride: usize,
+    vertex_vec: Vec<ScalarVertex>,
+    vertex_buffer: wgpu::Buffer,
+    vertex_offset: usize,
+
+    style_stride: usize,
+    style_vec: Vec<ScalarStyle>,
+    style_buffer: wgpu::Buffer,
+    style_offset: usize,
+
+    items: Vec<ScalarItem>,
+    textures: Vec<ScalarTextures>,
+
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+
+    is_stale: bool,
+}
+
+impl ScalarImageRender {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        let len = 64;
+
+        let mut vertex_vec = Vec::<ScalarVertex>::new();
+        vertex_vec.resize(6 * len, ScalarVertex::empty());
+
+        let vertex_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(vertex_vec.as_slice()),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let mut style_vec = Vec::<ScalarStyle>::new();
+        style_vec.resize(len, ScalarStyle::empty());
+
+        let style_buffer = device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(style_vec.as_slice()),
+                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            }
+        );
+
+        let layout = create_bind_group_layout(device);
+
+        let pipeline = create_scalar_pipeline(device, &layout, format, sample_count);
+
+        Self {
+            vertex_stride: std::mem::size_of::<ScalarVertex>(),
+            vertex_vec,
+            vertex_buffer,
+            vertex_offset: 0,
+
+            style_stride: std::mem::size_of::<ScalarStyle>(),
+            style_vec,
+            style_buffer,
+            style_offset: 0,
+
+            items: Vec::new(),
+            textures: Vec::new(),
+
+            layout,
+            pipeline,
+
+            is_stale: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.drain(..);
+        self.vertex_offset = 0;
+        self.style_offset = 0;
+
+        // drop the shapes this frame didn't draw once the pool grows too big
+        if self.textures.len() > SCALAR_TEXTURE_MAX {
+            self.textures.retain(|t| t.is_used);
+        }
+
+        for textures in &mut self.textures {
+            textures.is_used = false;
+        }
+    }
+
+    pub(crate) fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        pos: &Bounds<Canvas>,
+        values: &Tensor<f32>,
+        map: &ScalarMap,
+        affine: &Affine2d,
+    ) -> Result<(), RenderErr> {
+        if values.rank() != 2 || values.dim(0) == 0 || values.dim(1) == 0 {
+            return Err(RenderErr::InvalidShape);
+        }
+
+        let rows = values.dim(0) as u32;
+        let cols = values.dim(1) as u32;
+        let n_lut = map.get_colors().len() as u32;
+
+        let max = device.limits().max_texture_dimension_2d;
+
+        if rows > max || cols > max || n_lut > max {
+            return Err(RenderErr::TooLarge);
+        }
+
+        if self.style_offset == self.style_vec.len() {
+            self.is_stale = true;
+
+            let len = self.style_vec.len();
+            self.style_vec.resize(2 * len, ScalarStyle::empty());
+            self.vertex_vec.resize(12 * len, ScalarVertex::empty());
+        }
+
+        let start = self.vertex_offset;
+
+        let (x0, y0, x1, y1) = (pos.x0(), pos.y0(), pos.x1(), pos.y1());
+
+        // rows are drawn bottom to top, like images
+        self.vertex(x0, y0, 0., 0.);
+        self.vertex(x0, y1, 0., 1.);
+        self.vertex(x1, y1, 1., 1.);
+
+        self.vertex(x1, y1, 1., 1.);
+        self.vertex(x1, y0, 1., 0.);
+        self.vertex(x0, y0, 0., 0.);
+
+        let end = self.vertex_offset;
+
+        let index = self.style_offset;
+        self.style_vec[index] = ScalarStyle::new(affine, map);
+        self.style_offset += 1;
+
+        let texture = self.texture(device, rows, cols, n_lut);
+
+        let lut = map.get_colors().iter().map(|c| c.to_lrgb()).collect();
+
+        self.items.push(ScalarItem {
+            start,
+            end,
+            index,
+            values: values.clone(),
+            lut,
+            texture,
+        });
+
+        Ok(())
+    }
+
+    ///
+    /// Returns a pooled texture pair for the shape that isn't already used
+    /// this frame, creating one when none is free. Each item needs its own
+    /// pair, because all writes land before the queue submits.
+    ///
+    fn texture(
+        &mut self,
+        device: &wgpu::Device,
+        rows: u32,
+        cols: u32,
+        n_lut: u32,
+    ) -> usize {
+        let free = self.textures.iter().position(|t| {
+            ! t.is_used && t.rows == rows && t.cols == cols && t.n_lut == n_lut
+        });
+
+        let index = match free {
+            Some(index) => index,
+            None => {
+                self.textures.push(ScalarTextures::new(device, &self.layout, rows, cols, n_lut));
+                self.textures.len() - 1
+            }
+        };
+
+        self.textures[index].is_used = true;
+
+        index
+    }
+
+    pub(crate) fn item_count(&self) -> usize {
+        self.items.len()
+    }
+
+    ///
+    /// Writes the vertex and style buffers and every item's textures once
+    /// per flush, before any batch is drawn
+    ///
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        if self.is_stale {
+            self.is_stale = false;
+
+            self.vertex_buffer = device.create_buffer_init(
+                &wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(self.vertex_vec.as_slice()),
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                }
+            );
+
+            self.style_buffer = device.create_buffer_init(
+                &wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytemuck::cast_slice(self.style_vec.as_slice()),
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                }
+            );
+        }
+
+        queue.write_buffer(
+            &mut self.vertex_buffer,
+            0,
+            bytemuck::cast_slice(&self.vertex_vec[..self.vertex_offset])
+        );
+
+        queue.write_buffer(
+            &mut self.style_buffer,
+            0,
+            bytemuck::cast_slice(&self.style_vec[..self.style_offset])
+        );
+
+        for item in &self.items {
+            item.write_textures(queue, &self.textures[item.texture]);
+        }
+    }
+
+    ///
+    /// Draws a batch of items from the buffers written by prepare
+    ///
+    pub fn flush(
+        &mut self,
+        view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        encoder: &mut wgpu::CommandEncoder,
+        scissor: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        if let Some((x, y, w, h)) = scissor {
+            rpass.set_scissor_rect(x, y, w, h);
+        }
+
+        rpass.set_pipeline(&self.pipeline);
+
+        for item in &self.items[items] {
+            let stride = self.vertex_stride;
+            rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
+                (stride * item.start) as u64..(stride * item.end) as u64
+            ));
+
+            let stride = self.style_stride;
+            rpass.set_vertex_buffer(1, self.style_buffer.slice(
+                (stride * item.index) as u64..(stride * (item.index + 1)) as u64
+            ));
+
+            rpass.set_bind_group(0, &self.textures[item.texture].bind_group, &[]);
+
+            rpass.draw(0..(item.end - item.start) as u32, 0..1);
+        }
+    }
+
+    fn vertex(&mut self, x: f32, y: f32, u: f32, v: f32) {
+        self.vertex_vec[self.vertex_offset] = ScalarVertex {
+            position: [x.round(), y.round()],
+            tex_coord: [u, v],
+        };
+
+        self.vertex_offset += 1;
+    }
+}
+
+struct ScalarItem {
+    start: usize,
+    end: usize,
+    index: usize,
+
+    values: Tensor<f32>,
+    lut: Vec<[f32; 4]>,
+
+    texture: usize,
+}
+
+impl ScalarItem {
+    fn write_textures(&self, queue: &wgpu::Queue, textures: &ScalarTextures) {
+        let (rows, cols, n_lut) = (textures.rows, textures.cols, textures.n_lut);
+
+        write_texture(queue, &textures.values_texture, bytemuck::cast_slice(self.values.as_slice()), 4, cols, rows);
+
+        write_texture(queue, &textures.lut_texture, bytemuck::cast_slice(self.lut.as_slice()), 16, n_lut, 1);
+    }
+}
+
+const SCALAR_TEXTURE_MAX: usize = 16;
+
+struct ScalarTextures {
+    rows: u32,
+    cols: u32,
+    n_lut: u32,
+
+    values_texture: wgpu::Texture,
+    lut_texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+
+    is_used: bool,
+}
+
+impl ScalarTextures {
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        rows: u32,
+        cols: u32,
+        n_lut: u32,
+    ) -> Self {
+        let values_texture = create_texture(
+            device,
+            wgpu::TextureFormat::R32Float,
+            cols,
+            rows,
+            "scalar_values"
+        );
+
+        let lut_texture = create_texture(
+            device,
+            wgpu::TextureFormat::Rgba32Float,
+            n_lut,
+            1,
+            "scalar_lut"
+        );
+
+        let bind_group = create_bind_group(device, layout, &values_texture, &lut_texture);
+
+        Self {
+            rows,
+            cols,
+            n_lut,
+            values_texture,
+            lut_texture,
+            bind_group,
+            is_used: false,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct ScalarVertex {
+    position: [f32; 2],
+    tex_coord: [f32; 2],
+}
+
+impl ScalarVertex {
+    const ATTRS: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2 ];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ScalarVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRS,
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            position: [0., 0.],
+            tex_coord: [0., 0.],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct ScalarStyle {
+    affine_0: [f32; 4],
+    affine_1: [f32; 4],
+    // vmin, vmax, lookup table size
+    range: [f32; 4],
+    nan_color: [f32; 4],
+    over_color: [f32; 4],
+    under_color: [f32; 4],
+}
+
+impl ScalarStyle {
+    const ATTRS: [wgpu::VertexAttribute; 6] =
+        wgpu::vertex_attr_array![
+            2 => Float32x4,
+            3 => Float32x4,
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Float32x4,
+            7 => Float32x4
+        ];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ScalarStyle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRS,
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            affine_0: [0., 0., 0., 0.],
+            affine_1: [0., 0., 0., 0.],
+            range: [0., 0., 0., 0.],
+            nan_color: [0., 0., 0., 0.],
+            over_color: [0., 0., 0., 0.],
+            under_color: [0., 0., 0., 0.],
+        }
+    }
+
+    fn new(affine: &Affine2d, map: &ScalarMap) -> Self {
+        let mat = affine.mat();
+
+        Self {
+            affine_0: [mat[0], mat[1], 0., mat[2]],
+            affine_1: [mat[3], mat[4], 0., mat[5]],
+            range: [
+                map.get_vmin(),
+                map.get_vmax(),
+                map.get_colors().len() as f32,
+                0.
+            ],
+            nan_color: map.get_nan_color().to_lrgb(),
+            over_color: map.get_over_color().to_lrgb(),
+            under_color: map.get_under_color().to_lrgb(),
+        }
+    }
+}
+
+fn write_texture(
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    data: &[u8],
+    texel_size: u32,
+    width: u32,
+    height: u32,
+) {
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(texel_size * width),
+            rows_per_image: Some(height),
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+fn create_texture(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    label: &str,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    })
+}
+
+fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    let entry = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+        },
+        count: None,
+    };
+
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &[
+            entry(0),
+            entry(1),
+        ],
+        label: Some("scalar_bind_group_layout"),
+    })
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    values: &wgpu::Texture,
+    lut: &wgpu::Texture,
+) -> wgpu::BindGroup {
+    let values_view = values.create_view(&wgpu::TextureViewDescriptor::default());
+    let lut_view = lut.create_view(&wgpu::TextureViewDescriptor::default());
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&values_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&lut_view),
+            },
+        ],
+        label: Some("scalar_bind_group"),
+    })
+}
+
+fn create_scalar_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+    let shader = device.create_shader_module(wgpu::include_wgsl!("scalar_image.wgsl"));
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[
+            layout,
+        ],
+        push_constant_ranges: &[],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_scalar",
+            buffers: &[
+                ScalarVertex::desc(),
+                ScalarStyle::desc(),
+            ],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_scalar",
+            targets: &[
+                Some(wgpu::ColorTargetState {
+                    format,
+
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent {
+                            src_factor: wgpu::BlendFactor::SrcAlpha,
+                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                            operation: wgpu::BlendOperation::Add
+                        },
+
+                        alpha: wgpu::BlendComponent::OVER
+                    }),
+
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            ],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
+        multiview: None,
+    })
+}
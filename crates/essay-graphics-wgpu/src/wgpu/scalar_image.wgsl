@@ -0,0 +1,85 @@
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) tex_coord: vec2<f32>,
+}
+
+struct StyleInput {
+    @location(2) a0: vec4<f32>,
+    @location(3) a1: vec4<f32>,
+    // vmin, vmax, lookup table size
+    @location(4) range: vec4<f32>,
+    @location(5) nan_color: vec4<f32>,
+    @location(6) over_color: vec4<f32>,
+    @location(7) under_color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) tex_coord: vec2<f32>,
+    @location(1) @interpolate(flat) range: vec4<f32>,
+    @location(2) @interpolate(flat) nan_color: vec4<f32>,
+    @location(3) @interpolate(flat) over_color: vec4<f32>,
+    @location(4) @interpolate(flat) under_color: vec4<f32>,
+};
+
+@vertex
+fn vs_scalar(
+    model: VertexInput,
+    style: StyleInput,
+) -> VertexOutput {
+    let a0 = style.a0;
+    let a1 = style.a1;
+    let xp = model.pos[0];
+    let yp = model.pos[1];
+    let x = a0[0] * xp + a0[1] * yp + a0[3];
+    let y = a1[0] * xp + a1[1] * yp + a1[3];
+    var out: VertexOutput;
+    out.pos = vec4<f32>(x, y, 0.0, 1.0);
+    out.tex_coord = model.tex_coord;
+    out.range = style.range;
+    out.nan_color = style.nan_color;
+    out.over_color = style.over_color;
+    out.under_color = style.under_color;
+    return out;
+}
+
+@group(0) @binding(0)
+var t_values: texture_2d<f32>;
+
+@group(0) @binding(1)
+var t_lut: texture_2d<f32>;
+
+@fragment
+fn fs_scalar(
+    in: VertexOutput,
+) -> @location(0) vec4<f32> {
+    let dims = vec2<i32>(textureDimensions(t_values));
+    let ij = clamp(vec2<i32>(in.tex_coord * vec2<f32>(dims)), vec2<i32>(0, 0), dims - 1);
+    let value = textureLoad(t_values, ij, 0).r;
+
+    // NaN by bit pattern, because compilers may fold `value != value`
+    // to false: exponent all ones and a non-zero mantissa
+    let bits = bitcast<u32>(value);
+    if ((bits & 0x7f800000u) == 0x7f800000u && (bits & 0x007fffffu) != 0u) {
+        return in.nan_color;
+    }
+
+    let vmin = in.range.x;
+    let vmax = in.range.y;
+    let n = i32(in.range.z);
+
+    var t = 0.0;
+    if (vmax > vmin) {
+        t = (value - vmin) / (vmax - vmin);
+    }
+
+    if (t < 0.0) {
+        return in.under_color;
+    } else if (t > 1.0) {
+        return in.over_color;
+    }
+
+    let index = min(i32(t * f32(n)), n - 1);
+
+    return textureLoad(t_lut, vec2<i32>(index, 0), 0);
+}
@@ -2,7 +2,7 @@ use bytemuck_derive::{Zeroable, Pod};
 use essay_graphics_api::{Affine2d, Color, Point};
 use wgpu::util::DeviceExt;
 
-use super::canvas::line_normal;
+use super::{canvas::line_normal, gradient::{lut_bind_group_layout, PaintStyle}};
 
 pub struct Shape2dRender {
     vertex_stride: usize,
@@ -21,12 +21,15 @@ pub struct Shape2dRender {
 
     // texture_cache: TextureCache,
     pipeline: wgpu::RenderPipeline,
+    // single-sample pipeline for non-antialiased paths with msaa
+    pipeline_alias: Option<wgpu::RenderPipeline>,
 }
 
 impl Shape2dRender {
     pub(crate) fn new(
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -46,6 +49,8 @@ impl Shape2dRender {
             affine_0: [0.0, 0.0, 0.0, 0.0], 
             affine_1: [0.0, 0.0, 0.0, 0.0], 
             color: [0.0, 0.0, 0.0, 0.0],
+            paint_0: [0.0, 0.0, 0.0, 0.0],
+            paint_1: [0.0, 0.0, 0.0, 0.0],
         });
 
         let style_buffer = device.create_buffer_init(
@@ -59,7 +64,14 @@ impl Shape2dRender {
         let pipeline = create_shape2d_pipeline(
             device, 
             format,
+            sample_count,
         );
+
+        let pipeline_alias = if sample_count > 1 {
+            Some(create_shape2d_pipeline(device, format, 1))
+        } else {
+            None
+        };
     
         Self {
             vertex_stride: std::mem::size_of::<Shape2dVertex>(),
@@ -78,6 +90,7 @@ impl Shape2dRender {
             shape_items: Vec::new(),
             // texture_cache: TextureCache::new(),
             pipeline,
+            pipeline_alias,
         }
     }
 
@@ -135,6 +148,19 @@ impl Shape2dRender {
         &mut self, 
         color: Color,
         affine: &Affine2d,
+    ) {
+        self.draw_style_paint(color, PaintStyle::solid(), affine);
+    }
+
+    ///
+    /// Draws the current shape with a gradient paint, where color is
+    /// multiplied with the gradient's color.
+    /// 
+    pub(crate) fn draw_style_paint(
+        &mut self, 
+        color: Color,
+        paint: PaintStyle,
+        affine: &Affine2d,
     ) {
         let end = self.vertex_offset;
 
@@ -148,26 +174,28 @@ impl Shape2dRender {
         let item = &mut self.shape_items[len - 1];
         item.v_end = end;
 
-        self.style_vec[self.style_offset] = Shape2dStyle::new(affine, color);
+        self.style_vec[self.style_offset] = Shape2dStyle::new(affine, color, paint);
         self.style_offset += 1;
 
         item.s_end = self.style_offset;
     }
 
-    pub fn flush(
+    pub(crate) fn item_count(&self) -> usize {
+        self.shape_items.len()
+    }
+
+    ///
+    /// Writes the vertex and style buffers before any draw_item calls
+    /// 
+    pub fn prepare(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
-        view: &wgpu::TextureView,
-        encoder: &mut wgpu::CommandEncoder,
-        scissor: Option<(u32, u32, u32, u32)>,
     ) {
         if self.shape_items.len() == 0 {
             return;
         }
 
-        // self.texture_cache.flush(queue);
-
         if self.is_stale {
             self.is_stale = false;
  
@@ -199,52 +227,45 @@ impl Shape2dRender {
             0,
             bytemuck::cast_slice(self.style_vec.as_slice())
         );
+    }
 
-        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: None,
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: wgpu::StoreOp::Store,
-                }
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
-
-        rpass.set_pipeline(&self.pipeline);
+    ///
+    /// Draws a single shape item into an existing render pass. The item
+    /// index is the draw order, which is shared with the bezier renderer.
+    /// 
+    pub fn draw_item<'a>(
+        &'a self, 
+        rpass: &mut wgpu::RenderPass<'a>,
+        index: usize,
+        is_alias: bool,
+    ) {
+        let item = &self.shape_items[index];
 
-        if let Some((x, y, w, h)) = scissor {
-            rpass.set_scissor_rect(x, y, w, h);
-        }
+        if item.v_start < item.v_end && item.s_start < item.s_end {
+            match &self.pipeline_alias {
+                Some(pipeline) if is_alias => rpass.set_pipeline(pipeline),
+                _ => rpass.set_pipeline(&self.pipeline),
+            }
 
-        for item in self.shape_items.drain(..) {
-            if item.v_start < item.v_end && item.s_start < item.s_end {
-                if let Some([x, y, w, h]) = item.clip {
-                    rpass.set_viewport(x, y, w, h, f32::MIN, f32::MAX);
-                }
+            if let Some([x, y, w, h]) = item.clip {
+                rpass.set_viewport(x, y, w, h, f32::MIN, f32::MAX);
+            }
 
-                let stride = self.vertex_stride;
-                rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
-                    (stride * item.v_start) as u64..(stride * item.v_end) as u64
-                ));
+            let stride = self.vertex_stride;
+            rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
+                (stride * item.v_start) as u64..(stride * item.v_end) as u64
+            ));
 
-                let stride = self.style_stride;
-                rpass.set_vertex_buffer(1, self.style_buffer.slice(
-                    (stride * item.s_start) as u64..(stride * item.s_end) as u64
-                ));
+            let stride = self.style_stride;
+            rpass.set_vertex_buffer(1, self.style_buffer.slice(
+                (stride * item.s_start) as u64..(stride * item.s_end) as u64
+            ));
 
-                rpass.draw(
-                    0..(item.v_end - item.v_start) as u32,
-                    0..(item.s_end - item.s_start) as u32,
-                );
-            }
+            rpass.draw(
+                0..(item.v_end - item.v_start) as u32,
+                0..(item.s_end - item.s_start) as u32,
+            );
         }
-
-        self.vertex_offset = 0;
     }
 
     fn vertex(&mut self, x: f32, y: f32) {
@@ -308,14 +329,18 @@ pub struct Shape2dStyle {
     affine_0: [f32; 4],
     affine_1: [f32; 4],
     color: [f32; 4],
+    paint_0: [f32; 4],
+    paint_1: [f32; 4],
 }
 
 impl Shape2dStyle {
-    const ATTRS: [wgpu::VertexAttribute; 3] =
+    const ATTRS: [wgpu::VertexAttribute; 5] =
         wgpu::vertex_attr_array![
             1 => Float32x4, 
             2 => Float32x4,
-            3 => Float32x4
+            3 => Float32x4,
+            4 => Float32x4,
+            5 => Float32x4
         ];
 
     pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
@@ -331,10 +356,12 @@ impl Shape2dStyle {
             affine_0: [0., 0., 0., 0.],
             affine_1: [0., 0., 0., 0.],
             color: [0., 0., 0., 0.],
+            paint_0: [0., 0., 0., 0.],
+            paint_1: [0., 0., 0., 0.],
         }
     }
 
-    fn new(affine: &Affine2d, color: Color) -> Self {
+    fn new(affine: &Affine2d, color: Color, paint: PaintStyle) -> Self {
         let mat = affine.mat();
 
         Self {
@@ -346,6 +373,8 @@ impl Shape2dStyle {
                 Color::srgb_to_lrgb(color.blue()),
                 color.alpha(),
             ],
+            paint_0: paint.geom,
+            paint_1: paint.mode,
         }
     }
 }
@@ -353,8 +382,14 @@ impl Shape2dStyle {
 fn create_shape2d_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
-    let shader = device.create_shader_module(wgpu::include_wgsl!("shape2d.wgsl"));
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("shape2d.wgsl"),
+        source: wgpu::ShaderSource::Wgsl(
+            concat!(include_str!("paint.wgsl"), include_str!("shape2d.wgsl")).into()
+        ),
+    });
 
     let vertex_entry = "vs_shape";
     let fragment_entry = "fs_shape";
@@ -365,6 +400,7 @@ fn create_shape2d_pipeline(
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: None,
         bind_group_layouts: &[
+            &lut_bind_group_layout(device),
         ],
         push_constant_ranges: &[],
     });
@@ -403,7 +439,10 @@ fn create_shape2d_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
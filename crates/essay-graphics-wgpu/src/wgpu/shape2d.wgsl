@@ -15,13 +15,20 @@ struct StyleInput {
     @location(1) a0: vec4<f32>,
     @location(2) a1: vec4<f32>,
     @location(3) color: vec4<f32>,
+    @location(4) paint_0: vec4<f32>,
+    @location(5) paint_1: vec4<f32>,
 }
 
 struct VertexOutput {
     @location(0) color: vec4<f32>,
+    @location(1) paint_pos: vec2<f32>,
+    @location(2) @interpolate(flat) paint_0: vec4<f32>,
+    @location(3) @interpolate(flat) paint_1: vec4<f32>,
     @builtin(position) pos: vec4<f32>,
 };
 
+// paint() and t_lut are in paint.wgsl
+
 @vertex
 fn vs_shape(
     model: VertexInput,
@@ -36,6 +43,9 @@ fn vs_shape(
     var out: VertexOutput;
     out.pos = vec4<f32>(x, y, 0.0, 1.0);
     out.color = style.color;
+    out.paint_pos = model.pos;
+    out.paint_0 = style.paint_0;
+    out.paint_1 = style.paint_1;
     return out;
 }
 
@@ -43,5 +53,5 @@ fn vs_shape(
 fn fs_shape(
     in: VertexOutput,
 ) -> @location(0) vec4<f32> {
-    return in.color;
+    return paint(in.color, in.paint_pos, in.paint_0, in.paint_1);
 }
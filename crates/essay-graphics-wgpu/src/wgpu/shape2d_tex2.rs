@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bytemuck_derive::{Pod, Zeroable};
 use essay_graphics_api::{form::{Shape, ShapeId}, Affine2d, TextureId};
 use wgpu::util::DeviceExt;
@@ -30,6 +32,7 @@ impl Shape2dTex2Render {
     pub(crate) fn new(
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -64,6 +67,7 @@ impl Shape2dTex2Render {
         let pipeline = form3d_pipeline(
             device, 
             format,
+            sample_count,
         );
     
         Self {
@@ -131,7 +135,7 @@ impl Shape2dTex2Render {
     }
 
     pub fn draw_shape(&mut self, shape: ShapeId) {
-        self.draw_items.push(DrawItem::new(shape));
+        self.draw_items.push(DrawItem::new(shape, self.camera));
     }
 
     pub fn camera(
@@ -141,16 +145,22 @@ impl Shape2dTex2Render {
         self.camera.set(camera);
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.draw_items.len()
+    }
+
     pub fn flush(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
         textures: &TextureCache,
-        clip: Option<(u32, u32, u32, u32)>
+        clip: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
     ) {
-        if self.draw_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -158,7 +168,7 @@ impl Shape2dTex2Render {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -191,10 +201,12 @@ impl Shape2dTex2Render {
             );
         }
 
+        let camera = self.draw_items[items.start].camera;
+
         queue.write_buffer(
             &mut self.camera_buffer,
             0,
-            bytemuck::cast_slice(&[self.camera])
+            bytemuck::cast_slice(&[camera])
         );
 
         rpass.set_pipeline(&self.pipeline);
@@ -206,7 +218,7 @@ impl Shape2dTex2Render {
             rpass.set_scissor_rect(x, y, w, h);
         }
 
-        for draw_item in self.draw_items.drain(..) {
+        for draw_item in &self.draw_items[items] {
             let item = &self.form_items[draw_item.id.0];
 
             rpass.set_bind_group(0, textures.texture_bind_group(item.texture), &[]);
@@ -235,12 +247,15 @@ struct FormItem {
 
 struct DrawItem {
     id: ShapeId,
+    // camera at draw time, because items can be flushed after a later camera change
+    camera: CameraUniform,
 }
 
 impl DrawItem {
-    fn new(id: ShapeId) -> Self {
+    fn new(id: ShapeId, camera: CameraUniform) -> Self {
         Self {
             id,
+            camera,
         }
     }
 }
@@ -302,6 +317,7 @@ impl CameraUniform {
 fn form3d_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::include_wgsl!("shape2d_tex2.wgsl"));
 
@@ -352,7 +368,10 @@ fn form3d_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, ops::{Index, IndexMut}};
+use std::{collections::HashMap, ops::Range};
 
 use bytemuck_derive::{Zeroable, Pod};
 use essay_graphics_api::{Affine2d, Color, Hatch, Point, TextureId};
@@ -22,7 +22,8 @@ pub struct Shape2dTextureRender {
     is_stale: bool,
 
     texture_cache: TextureCache,
-    hatch_map: HashMap<Hatch, TextureId>,
+    // hatch textures keyed by the line width in quarter pixels
+    hatch_map: HashMap<(Hatch, u32), TextureId>,
     
     pipeline: wgpu::RenderPipeline,
 }
@@ -30,8 +31,8 @@ pub struct Shape2dTextureRender {
 impl Shape2dTextureRender {
     pub(crate) fn new(
         device: &wgpu::Device, 
-        queue: &wgpu::Queue,
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -64,12 +65,12 @@ impl Shape2dTextureRender {
             }
         );
 
-        let mut textures = TextureCache::new();
-        let hatch_map = init_hatch(device, queue, &mut textures);
+        let textures = TextureCache::new();
 
         let pipeline = create_shape2d_pipeline(
             device, 
             format,
+            sample_count,
         );
     
         Self {
@@ -88,13 +89,36 @@ impl Shape2dTextureRender {
 
             shape_items: Vec::new(),
             texture_cache: textures,
-            hatch_map: hatch_map,
+            hatch_map: HashMap::new(),
             pipeline,
         }
     }
 
-    pub fn hatch_texture(&self, hatch: Hatch) -> TextureId {
-        *self.hatch_map.get(&hatch).unwrap()
+    ///
+    /// Creates the tiled hatch texture if it's not already cached. The line
+    /// width is in pixels.
+    /// 
+    pub(crate) fn add_hatch(
+        &mut self, 
+        device: &wgpu::Device, 
+        queue: &wgpu::Queue, 
+        hatch: Hatch,
+        line_width: f32,
+    ) {
+        let key = (hatch, hatch_key(line_width));
+
+        if ! self.hatch_map.contains_key(&key) {
+            let size = HATCH_SIZE as u32;
+            let data = hatch.to_mask(HATCH_SIZE, line_width);
+
+            let texture = self.texture_cache.add_r_u8(device, queue, size, size, &data);
+
+            self.hatch_map.insert(key, texture);
+        }
+    }
+
+    pub fn hatch_texture(&self, hatch: Hatch, line_width: f32) -> Option<TextureId> {
+        self.hatch_map.get(&(hatch, hatch_key(line_width))).map(|id| *id)
     }
 
     fn texture_bind_map(&self, id: TextureId) -> &wgpu::BindGroup {
@@ -105,6 +129,13 @@ impl Shape2dTextureRender {
         self.shape_items.drain(..);
         self.vertex_offset = 0;
         self.style_offset = 0;
+
+        // an animated hatch width creates a texture per frame, so the
+        // cache, which only holds hatches, is dropped once it's too large
+        if self.hatch_map.len() > HATCH_CACHE_MAX {
+            self.hatch_map.clear();
+            self.texture_cache = TextureCache::new();
+        }
     }
 
     pub fn start_shape(&mut self, texture: TextureId, clip: Option<[f32; 4]>) {
@@ -151,7 +182,7 @@ impl Shape2dTextureRender {
         //self.vertex_uv(p1.x(), p1.y(), 0., 10.);
         //self.vertex_uv(p2.x(), p2.y(), 10., 0.);
 
-        let f = 1. / 64.;
+        let f = 1. / HATCH_SIZE as f32;
 
         self.vertex_uv(p0.x(), p0.y(), p0.x() * f, p0.y() * f);
         self.vertex_uv(p1.x(), p1.y(), p1.x() * f, p1.y() * f);
@@ -181,15 +212,21 @@ impl Shape2dTextureRender {
         item.s_end = self.style_offset;
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.shape_items.len()
+    }
+
     pub fn flush(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
         scissor: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
     ) {
-        if self.shape_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -232,7 +269,7 @@ impl Shape2dTextureRender {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -249,8 +286,7 @@ impl Shape2dTextureRender {
             rpass.set_scissor_rect(x, y, w, h);
         }
 
-        let items : Vec<Item> = self.shape_items.drain(..).collect();
-        for item in items {
+        for item in &self.shape_items[items] {
             if item.v_start < item.v_end && item.s_start < item.s_end {
                 if let Some([x, y, w, h]) = item.clip {
                     rpass.set_viewport(x, y, w, h, f32::MIN, f32::MAX);
@@ -275,8 +311,6 @@ impl Shape2dTextureRender {
             }
         }
         }
-
-        self.vertex_offset = 0;
     }
 
     fn _vertex(&mut self, x: f32, y: f32) {
@@ -410,136 +444,20 @@ impl Style {
     }
 }
 
-fn init_hatch(device: &wgpu::Device, queue: &wgpu::Queue, textures: &mut TextureCache) -> HashMap<Hatch, TextureId> {
-    let mut hatch_map = HashMap::new();
-
-    hatch_map.insert(
-        Hatch::Vertical, 
-        hatch_vertical(device, queue, textures)
-    );
-    hatch_map.insert(
-        Hatch::Horizontal, 
-        hatch_horizontal(device, queue, textures)
-    );
-
-    hatch_map
-}
-
-fn hatch_vertical(
-    device: &wgpu::Device, 
-    queue: &wgpu::Queue, 
-    textures: &mut TextureCache
-) -> TextureId {
-    let mut builder = HatchBuilder::new(64, 64);
-
-    for j in 0..64 {
-        builder[(0, j)] = 255;
-        builder[(1, j)] = 255;
-        builder[(2, j)] = 255;
-        builder[(3, j)] = 255;
-
-        builder[(32, j)] = 255;
-        builder[(33, j)] = 255;
-        builder[(34, j)] = 255;
-        builder[(35, j)] = 255;
-    }
-
-    builder.add_to(device, queue, textures)
-
-}
-
-fn hatch_horizontal(
-    device: &wgpu::Device, 
-    queue: &wgpu::Queue, 
-    textures: &mut TextureCache
-) -> TextureId {
-    let mut builder = HatchBuilder::new(64, 64);
-
-    for j in 0..64 {
-        builder[(j, 0)] = 255;
-        builder[(j, 1)] = 255;
-        builder[(j, 2)] = 255;
-        builder[(j, 3)] = 255;
-
-        builder[(j, 32)] = 255;
-        builder[(j, 33)] = 255;
-        builder[(j, 34)] = 255;
-        builder[(j, 35)] = 255;
-    }
+// size in pixels of a hatch texture tile
+const HATCH_SIZE: usize = 64;
 
-    //for j in 17..32 {
-    //    builder[(j, 0)] = 255;
-    //}
+// cached hatch textures kept between frames
+const HATCH_CACHE_MAX: usize = 32;
 
-    builder.add_to(device, queue, textures)
-}
-
-
-
-struct HatchBuilder {
-    data: Vec<u8>,
-    width: usize,
-    height: usize,
-}
-
-impl HatchBuilder {
-    fn new(width: usize, height: usize) -> Self {
-        let mut vec = Vec::new();
-
-        vec.resize((width * height) as usize, 0);
-
-        Self {
-            data: vec,
-            width,
-            height
-        }
-    }
-
-    fn add_to(
-        self, 
-        device: &wgpu::Device, 
-        queue: &wgpu::Queue, 
-        textures: &mut TextureCache
-    ) -> TextureId {
-        textures.add_r_u8(
-            device, 
-            queue, 
-            self.width as u32, 
-            self.height as u32, 
-            self.as_slice()
-        )
-    }
-
-    fn as_slice(&self) -> &[u8] {
-        self.data.as_slice()
-    }
-
-    fn _set(&mut self, x: usize, y: usize, v: u8) -> &mut Self {
-        self.data[x + y * self.width] = v;
-
-        self
-    }
-}
-
-impl Index<(usize, usize)> for HatchBuilder {
-    type Output = u8;
-
-    #[inline]
-    fn index(&self, index: (usize, usize)) -> &Self::Output {
-        &self.data[index.0 + index.1 * self.width]
-    }
-}
-
-impl IndexMut<(usize, usize)> for HatchBuilder {
-    #[inline]
-    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
-        &mut self.data[index.0 + index.1 * self.width]
-    }
+fn hatch_key(line_width: f32) -> u32 {
+    (4. * line_width).round() as u32
 }
 
 fn create_shape2d_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::include_wgsl!("shape2d_texture.wgsl"));
 
@@ -591,7 +509,10 @@ fn create_shape2d_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
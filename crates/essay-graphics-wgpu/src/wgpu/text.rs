@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bytemuck_derive::{Zeroable, Pod};
 use essay_graphics_api::{Point, Color, Affine2d, HorizAlign, VertAlign};
 use wgpu::util::DeviceExt;
@@ -28,7 +30,8 @@ impl TextRender {
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
         width: u32, 
-        height: u32
+        height: u32,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -76,6 +79,7 @@ impl TextRender {
             GpuTextStyle::desc(),
             // style_layout,
             &texture,
+            sample_count,
         );
     
         Self {
@@ -98,10 +102,15 @@ impl TextRender {
     }
 
     pub fn clear(&mut self) {
+        self.text_items.drain(..);
         self.vertex_offset = 0;
         self.style_offset = 0;
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.text_items.len()
+    }
+
     ///
     /// load a font
     ///
@@ -208,11 +217,13 @@ impl TextRender {
         &mut self, 
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
+        items: Range<usize>,
     ) {
         self.text_cache.flush(queue, &self.texture);
 
-        if self.text_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -220,7 +231,7 @@ impl TextRender {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -243,7 +254,7 @@ impl TextRender {
             bytemuck::cast_slice(self.style_vec.as_slice())
         );
 
-        for item in self.text_items.drain(..) {
+        for item in &self.text_items[items] {
             rpass.set_pipeline(&self.pipeline);
 
             let stride = self.vertex_stride;
@@ -264,8 +275,6 @@ impl TextRender {
             );
 
         }
-
-        self.vertex_offset = 0;
     }
 
     fn vertex(&mut self, x: f32, y: f32, u: f32, v: f32) {
@@ -365,6 +374,7 @@ fn create_text_pipeline(
     style_layout: wgpu::VertexBufferLayout,
     // style_layout: wgpu::BindGroupLayout,
     texture: &TextTexture,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: None,
@@ -409,7 +419,10 @@ fn create_text_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
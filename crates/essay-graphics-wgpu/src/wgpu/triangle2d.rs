@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bytemuck_derive::{Pod, Zeroable};
 use essay_graphics_api::Affine2d;
 use wgpu::util::DeviceExt;
@@ -29,6 +31,7 @@ impl Triangle2dRenderer {
     pub(crate) fn new(
         device: &wgpu::Device, 
         format: wgpu::TextureFormat,
+        sample_count: u32,
     ) -> Self {
         let len = 2048;
 
@@ -74,6 +77,7 @@ impl Triangle2dRenderer {
         let pipeline = create_gridmesh2d_pipeline(
             device, 
             format,
+            sample_count,
         );
     
         Self {
@@ -176,15 +180,21 @@ impl Triangle2dRenderer {
         item.s_end = self.style_offset;
     }
 
+    pub(crate) fn item_count(&self) -> usize {
+        self.mesh_items.len()
+    }
+
     pub fn flush(
         &mut self, 
         device: &wgpu::Device,
         queue: &wgpu::Queue, 
         view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
         encoder: &mut wgpu::CommandEncoder,
         clip: Option<(u32, u32, u32, u32)>,
+        items: Range<usize>,
     ) {
-        if self.mesh_items.len() == 0 {
+        if items.is_empty() {
             return;
         }
 
@@ -192,7 +202,7 @@ impl Triangle2dRenderer {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view: &view,
-                resolve_target: None,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
@@ -255,7 +265,7 @@ impl Triangle2dRenderer {
             rpass.set_scissor_rect(x0, y0, w, h);
         }
 
-        for item in self.mesh_items.drain(..) {
+        for item in &self.mesh_items[items] {
             if item.v_start < item.v_end && item.i_start < item.i_end {
                 let stride = self.vertex_stride;
                 rpass.set_vertex_buffer(0, self.vertex_buffer.slice(
@@ -354,6 +364,7 @@ impl Style {
 fn create_gridmesh2d_pipeline(
     device: &wgpu::Device,
     format: wgpu::TextureFormat,
+    sample_count: u32,
 ) -> wgpu::RenderPipeline {
     let shader = device.create_shader_module(wgpu::include_wgsl!("triangle2d.wgsl"));
 
@@ -404,7 +415,10 @@ fn create_gridmesh2d_pipeline(
         }),
         primitive: wgpu::PrimitiveState::default(),
         depth_stencil: None,
-        multisample: wgpu::MultisampleState::default(),
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            ..Default::default()
+        },
         multiview: None,
     })
 }
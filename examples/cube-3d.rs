@@ -40,10 +40,10 @@ fn main() {
 
     layout.view(((0.5, 0.5), [0.5, 0.5]),
         CubeView::new(form, texture_colors(&[
-            Color::from("red"),
-            Color::from("blue"),
-            Color::from("orange"),
-            Color::from("teal"),
+            Color::try_from("red").unwrap(),
+            Color::try_from("blue").unwrap(),
+            Color::try_from("orange").unwrap(),
+            Color::try_from("teal").unwrap(),
         ]))
     );
 
@@ -172,43 +172,51 @@ impl Drawable for CubeView {
         Ok(())
     }
 
-    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
         match event {
             Event::KeyPress(_, 'w') => {
                 self.camera.forward(0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
             Event::KeyPress(_, 's') => {
                 self.camera.forward(-0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
             Event::KeyPress(_, 'a') => {
                 self.camera.right(-0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
             Event::KeyPress(_, 'd') => {
                 self.camera.right(0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
 
             Event::KeyPress(_, 'q') => {
                 self.camera.yaw(Angle::Deg(10.));
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
             Event::KeyPress(_, 'e') => {
                 self.camera.yaw(Angle::Deg(-10.));
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
 
             Event::KeyPress(_, 'r') => {
                 self.camera.up(0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
             Event::KeyPress(_, 'f') => {
                 self.camera.up(-0.1);
                 renderer.request_redraw(&Bounds::zero());
+                true
             }
-            _ => {}
+            _ => false,
         }
     }
 }
@@ -12,7 +12,7 @@ fn main() {
         .to_path();
 
     //figure.view(((0.0, 0.0), [0.25, 0.25]), PathView::new(path.clone()));
-    figure.view((0.2, 0.2, 2., 2.) , PathView::new(path.clone(), "teal"));
+    figure.view((0.2, 0.2, 2., 2.) , PathView::new(path.clone(), Color::try_from("teal").unwrap()));
 
     let path = Path::<Data>::move_to(0.1, 0.1)
         .line_to(0.1, 200.)
@@ -26,7 +26,7 @@ fn main() {
         .to_path();
     */
 
-    let view = figure.view(((0.6, 0.4), [0.25, 0.25]), PathView::new(path, "orange"));
+    let view = figure.view(((0.6, 0.4), [0.25, 0.25]), PathView::new(path, Color::try_from("orange").unwrap()));
     // let view = figure.view((), PathView::new(path));
 
     println!("Path {:?} ", view.read(|t| t.path()));
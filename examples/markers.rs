@@ -18,8 +18,8 @@ fn main() {
     ]);
 
     let colors = tensor!([
-        Color::from("red").to_rgba(),
-        Color::from("teal").to_rgba(),
+        Color::try_from("red").unwrap().to_rgba(),
+        Color::try_from("teal").unwrap().to_rgba(),
     ]);
 
     let scale = tensor!([
@@ -47,7 +47,7 @@ impl PathView {
 }
 
 impl Drawable for PathView {
-    fn event(&mut self, _renderer: &mut dyn Renderer, _event: &Event) {
+    fn event(&mut self, _renderer: &mut dyn Renderer, _event: &Event) -> bool {
         /*
         if let Event::Resize(pos) = event {
             let to_canvas = Bounds::<Data>::new((0., 0.), (1., 1.)).affine_to(pos);
@@ -55,6 +55,7 @@ impl Drawable for PathView {
             self.path = self.path_data.transform(&to_canvas);
         }
         */
+        false
     }
 
     fn draw(&mut self, renderer: &mut dyn Renderer) -> renderer::Result<()> {
@@ -25,10 +25,10 @@ fn main() {
 
     layout.view(((0.5, 0.5), [0.5, 0.5]),
         ShapeView::new(form, texture_colors(&[
-            Color::from("red"),
-            Color::from("blue"),
-            Color::from("orange"),
-            Color::from("teal"),
+            Color::try_from("red").unwrap(),
+            Color::try_from("blue").unwrap(),
+            Color::try_from("orange").unwrap(),
+            Color::try_from("teal").unwrap(),
         ]))
     );
 
@@ -53,10 +53,10 @@ impl Drawable for TriangleView {
     fn draw(&mut self, renderer: &mut dyn Renderer) -> renderer::Result<()> {
         let mut colors = Vec::<u32>::new();
 
-        colors.push(Color::from("teal").to_rgba());
-        colors.push(Color::from("red").to_rgba());
-        colors.push(Color::from("blue").to_rgba());
-        colors.push(Color::from("orange").to_rgba());
+        colors.push(Color::try_from("teal").unwrap().to_rgba());
+        colors.push(Color::try_from("red").unwrap().to_rgba());
+        colors.push(Color::try_from("blue").unwrap().to_rgba());
+        colors.push(Color::try_from("orange").unwrap().to_rgba());
 
         let colors = Tensor::from(colors);
 
@@ -65,9 +65,11 @@ impl Drawable for TriangleView {
         Ok(())
     }
 
-    fn event(&mut self, _renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, _renderer: &mut dyn Renderer, event: &Event) -> bool {
         if let Event::Resize(pos) = event {
             self.resize(pos);
         }
+
+        false
     }
 }
@@ -1,18 +1,41 @@
 use std::{any::Any, marker::PhantomData, sync::{Arc, Mutex}};
 
 use essay_graphics_api::{
-    renderer::{Result, Canvas, Drawable, Event, Renderer}, Bounds, Coord, Point
+    renderer::{Result, Canvas, Drawable, Event, Key, KeyState, MouseButton, Renderer}, Bounds, Coord, Point
 };
 
+///
+/// Views are stacked in the order they're added, so later views are on top.
+/// Pointer events go to the topmost view under the cursor first, and stop
+/// at the first view that handles them. A view that receives a button press
+/// captures the pointer, receiving moves, drags and releases even when the
+/// cursor leaves its bounds, until every button is released.
+///
+/// Key events go only to the focused view. A press focuses the view under
+/// the cursor, Tab and Shift-Tab that the focused view doesn't handle cycle
+/// focus in the order views were added,
+/// and View::focus focuses a view from its handle, taking effect at the end
+/// of the current event or at the next draw.
+///
 #[derive(Clone)]
 pub struct Layout {
     views: Vec<ViewItem>,
+    capture: Option<usize>,
+    // buttons held since the capture began
+    capture_buttons: Vec<MouseButton>,
+    focus: Option<usize>,
+    // the last Tab moved focus, so its text isn't sent to the new view
+    is_tab_focus: bool,
 }
 
 impl Layout {
     pub fn new() -> Self {
         Self {
             views: Vec::new(),
+            capture: None,
+            capture_buttons: Vec::new(),
+            focus: None,
+            is_tab_focus: false,
         }
     }
 
@@ -87,6 +110,183 @@ impl Layout {
         }
     }
 
+    // delivers topmost first until a view handles the event, returning the
+    // view that handled it
+    fn propagate(&mut self, renderer: &mut dyn Renderer, event: &Event) -> Option<usize> {
+        for (i, view) in self.views.iter_mut().enumerate().rev() {
+            if event.in_bounds(&view.pos_canvas) && view.ptr.event(renderer, event) {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    // enter and leave for the views whose hover changed
+    fn hover(&mut self, renderer: &mut dyn Renderer, point: Point) {
+        for view in self.views.iter_mut().rev() {
+            let is_hover = view.pos_canvas.contains(&point);
+
+            if is_hover && ! view.is_hover {
+                view.ptr.event(renderer, &Event::Enter(point));
+            } else if ! is_hover && view.is_hover {
+                view.ptr.event(renderer, &Event::Leave(point));
+            }
+
+            view.is_hover = is_hover;
+        }
+    }
+
+    fn press(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        let point = event.point();
+        let button = mouse_button(event);
+
+        // a chorded press goes to the view that already has the capture
+        if let Some(i) = self.capture {
+            if ! self.capture_buttons.contains(&button) {
+                self.capture_buttons.push(button);
+            }
+
+            return self.views[i].ptr.event(renderer, event);
+        }
+
+        let focus = self.views.iter()
+            .rposition(|view| view.pos_canvas.contains(&point));
+
+        self.set_focus(renderer, focus);
+
+        let is_handled = match self.propagate(renderer, event) {
+            Some(i) => {
+                self.capture = Some(i);
+
+                true
+            }
+            None => {
+                // an unhandled press still captures the topmost view, so
+                // views that only handle drags see the whole drag
+                self.capture = self.views.iter()
+                    .rposition(|view| view.pos_canvas.contains(&point));
+
+                false
+            }
+        };
+
+        if self.capture.is_some() {
+            self.capture_buttons.push(button);
+        }
+
+        is_handled
+    }
+
+    fn release(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        let i = match self.capture {
+            Some(i) => i,
+            None => return self.propagate(renderer, event).is_some(),
+        };
+
+        let is_handled = self.views[i].ptr.event(renderer, event);
+
+        let button = mouse_button(event);
+        self.capture_buttons.retain(|b| *b != button);
+
+        if self.capture_buttons.is_empty() {
+            self.capture = None;
+
+            // hover wasn't tracked during the capture
+            self.hover(renderer, event.point());
+        }
+
+        is_handled
+    }
+
+    fn drag(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        match self.capture {
+            Some(i) => self.views[i].ptr.event(renderer, event),
+            None => self.propagate(renderer, event).is_some(),
+        }
+    }
+
+    fn set_focus(&mut self, renderer: &mut dyn Renderer, focus: Option<usize>) {
+        if focus == self.focus {
+            return;
+        }
+
+        if let Some(i) = self.focus.take() {
+            let view = &mut self.views[i];
+
+            view.ptr.set_focus(false);
+            view.ptr.event(renderer, &Event::FocusLost);
+        }
+
+        if let Some(i) = focus {
+            let view = &mut self.views[i];
+
+            view.ptr.set_focus(true);
+            view.ptr.event(renderer, &Event::FocusGained);
+        }
+
+        self.focus = focus;
+    }
+
+    // focus requested by View::focus since the last check
+    fn focus_request(&mut self, renderer: &mut dyn Renderer) {
+        let mut request = None;
+
+        for (i, view) in self.views.iter_mut().enumerate() {
+            if view.ptr.take_focus_request() {
+                request = Some(i);
+            }
+        }
+
+        if request.is_some() {
+            self.set_focus(renderer, request);
+        }
+    }
+
+    fn focus_next(&mut self, renderer: &mut dyn Renderer, is_reverse: bool) {
+        let n = self.views.len();
+
+        if n == 0 {
+            return;
+        }
+
+        let focus = match (self.focus, is_reverse) {
+            (None, false) => 0,
+            (None, true) => n - 1,
+            (Some(i), false) => (i + 1) % n,
+            (Some(i), true) => (i + n - 1) % n,
+        };
+
+        self.set_focus(renderer, Some(focus));
+    }
+
+    fn key(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        let is_tab_focus = std::mem::take(&mut self.is_tab_focus);
+
+        if is_tab_focus && matches!(event, Event::KeyPress(_, '\t')) {
+            return true;
+        }
+
+        // the focused view sees Tab first, so text views can insert it
+        if let Some(i) = self.focus {
+            if self.views[i].ptr.event(renderer, event) {
+                return true;
+            }
+        }
+
+        match event {
+            Event::Key(_, key) if key.key() == Key::Tab => {
+                if key.state() != KeyState::Release {
+                    self.focus_next(renderer, key.modifiers().shift);
+                    self.is_tab_focus = true;
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn bounds(&self) -> Bounds<Layout> {
         let mut bounds = Bounds::unit();
 
@@ -100,6 +300,8 @@ impl Layout {
 
 impl Drawable for Layout {
     fn draw(&mut self, renderer: &mut dyn Renderer) -> Result<()> {
+        self.focus_request(renderer);
+
         for item in &mut self.views {
             renderer.draw_with(&item.pos_canvas, &mut item.ptr)?;
         }
@@ -107,34 +309,100 @@ impl Drawable for Layout {
         Ok(())
     }
 
-    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) {
-        match event {
+    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        self.focus_request(renderer);
+
+        let is_handled = match event {
             Event::Resize(bounds) => {
                 self.layout(renderer, bounds);
 
                 for view in &mut self.views {
                     view.ptr.event(renderer, &Event::Resize(view.pos_canvas.clone()));
                 }
-            },
-            _ => {
-                // let point = event.point();
 
+                false
+            },
+            Event::MouseMove(point) => {
+                match self.capture {
+                    // only the captured view sees moves, without enter
+                    // and leave, until its buttons are released
+                    Some(i) => self.views[i].ptr.event(renderer, event),
+                    None => {
+                        // enter and leave go to every view, but the move
+                        // itself stops at the view that handles it
+                        self.hover(renderer, *point);
+
+                        self.propagate(renderer, event).is_some()
+                    }
+                }
+            }
+            // views are entered by the following MouseMove
+            Event::Enter(_) => false,
+            Event::Leave(_) => {
                 for view in &mut self.views {
-                    if event.in_bounds(&view.pos_canvas) {
+                    if view.is_hover {
+                        view.is_hover = false;
+
                         view.ptr.event(renderer, event);
                     }
                 }
+
+                false
             }
-        }
+            Event::MouseLeftPress(..)
+            | Event::MouseRightPress(..)
+            | Event::MouseMiddlePress(..)
+            | Event::MousePress(..) => self.press(renderer, event),
+
+            Event::MouseLeftRelease(..)
+            | Event::MouseRightRelease(..)
+            | Event::MouseMiddleRelease(..)
+            | Event::MouseRelease(..) => self.release(renderer, event),
+
+            Event::MouseLeftDrag(..)
+            | Event::MouseRightDrag(..)
+            | Event::MouseMiddleDrag(..)
+            | Event::MouseDrag(..)
+            | Event::Pan(..)
+            | Event::ZoomBounds(..) => self.drag(renderer, event),
+
+            Event::Key(..) | Event::KeyPress(..) => self.key(renderer, event),
+
+            // a nested layout passes its own focus to its focused view
+            Event::FocusGained | Event::FocusLost => {
+                match self.focus {
+                    Some(i) => self.views[i].ptr.event(renderer, event),
+                    None => false,
+                }
+            }
+
+            _ => self.propagate(renderer, event).is_some(),
+        };
+
+        // focus requested by a view while handling the event
+        self.focus_request(renderer);
+
+        is_handled
     }
 }
 
 impl Coord for Layout {}
 
+fn mouse_button(event: &Event) -> MouseButton {
+    match event {
+        Event::MouseLeftPress(..) | Event::MouseLeftRelease(..) => MouseButton::Left,
+        Event::MouseRightPress(..) | Event::MouseRightRelease(..) => MouseButton::Right,
+        Event::MouseMiddlePress(..) | Event::MouseMiddleRelease(..) => MouseButton::Middle,
+        Event::MousePress(_, button, _) | Event::MouseRelease(_, button, _) => *button,
+        _ => panic!("not a button press or release {:?}", event),
+    }
+}
+
 #[derive(Clone)]
 struct ViewItem {
     pos_grid: Bounds<Layout>,
     pos_canvas: Bounds<Canvas>,
+    is_hover: bool,
 
     ptr: ViewArc,
 }
@@ -144,6 +412,7 @@ impl ViewItem {
         Self {
             pos_grid: pos,
             pos_canvas: Bounds::none(),
+            is_hover: false,
             ptr: ViewArc(Arc::new(Mutex::new(ViewPtr::new(view))))
         }
     }
@@ -162,6 +431,27 @@ impl ViewArc {
     fn write<T: 'static, R>(&mut self, fun: impl FnOnce(&mut T) -> R) -> R {
         self.0.lock().unwrap().write(fun)
     }
+
+    fn set_focus(&mut self, is_focus: bool) {
+        self.0.lock().unwrap().is_focus = is_focus;
+    }
+
+    fn is_focus(&self) -> bool {
+        self.0.lock().unwrap().is_focus
+    }
+
+    fn request_focus(&self) {
+        self.0.lock().unwrap().is_focus_request = true;
+    }
+
+    fn take_focus_request(&mut self) -> bool {
+        let mut view = self.0.lock().unwrap();
+
+        let is_request = view.is_focus_request;
+        view.is_focus_request = false;
+
+        is_request
+    }
 }
 
 impl Drawable for ViewArc {
@@ -173,16 +463,19 @@ impl Drawable for ViewArc {
     }
 
     #[inline]
-    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
         let mut view = self.0.lock().unwrap();
         
-        view.event(renderer, event);
+        view.event(renderer, event)
     }
 }
 
 struct ViewPtr {
     ptr: Box<dyn Any + Send>,
     handle: Box<dyn ViewHandleTrait>,
+
+    is_focus: bool,
+    is_focus_request: bool,
 }
 
 impl ViewPtr {
@@ -190,6 +483,9 @@ impl ViewPtr {
         Self {
             ptr: Box::new(view),
             handle: Box::new(ViewHandle::<T>::new()),
+
+            is_focus: false,
+            is_focus_request: false,
         }
     }
 
@@ -199,8 +495,8 @@ impl ViewPtr {
     }
 
     #[inline]
-    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) {
-        self.handle.event(self.ptr.as_mut(), renderer, event);
+    fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+        self.handle.event(self.ptr.as_mut(), renderer, event)
     }
 
     #[inline]
@@ -216,7 +512,7 @@ impl ViewPtr {
 
 trait ViewHandleTrait : Send {
     fn draw(&mut self, any: &mut dyn Any, renderer: &mut dyn Renderer) -> Result<()>;
-    fn event(&mut self, any: &mut dyn Any, renderer: &mut dyn Renderer, event: &Event);
+    fn event(&mut self, any: &mut dyn Any, renderer: &mut dyn Renderer, event: &Event) -> bool;
 }
 
 struct ViewHandle<T: Drawable> {
@@ -238,7 +534,7 @@ impl<V: Drawable + 'static> ViewHandleTrait for ViewHandle<V> {
     }
 
     #[inline]
-    fn event(&mut self, any: &mut dyn Any, renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, any: &mut dyn Any, renderer: &mut dyn Renderer, event: &Event) -> bool {
         any.downcast_mut::<V>().unwrap().event(renderer, event)
     }
 }
@@ -268,6 +564,20 @@ impl<T: 'static> View<T> {
     pub fn write<R>(&mut self, fun: impl FnOnce(&mut T) -> R) -> R {
         self.view_arc.write(fun)
     }
+
+    ///
+    /// Requests key focus for the view, which the layout applies at the end
+    /// of the event it's dispatching, or otherwise at its next draw or
+    /// event.
+    ///
+    pub fn focus(&self) {
+        self.view_arc.request_focus();
+    }
+
+    #[inline]
+    pub fn is_focus(&self) -> bool {
+        self.view_arc.is_focus()
+    }
 }
 
 pub struct PosView {
@@ -291,21 +601,29 @@ impl Drawable for PosView {
         Ok(())
     }
 
-    fn event(&mut self, _renderer: &mut dyn Renderer, event: &Event) {
+    fn event(&mut self, _renderer: &mut dyn Renderer, event: &Event) -> bool {
         if let Event::Resize(pos) = event {
             self.pos = pos.clone();
         }
+
+        false
     }
 }
 
 #[cfg(test)]
 mod test {
-    use essay_graphics_api::{renderer::{Drawable, Event}, Bounds};
+    use std::time::Duration;
+
+    use essay_graphics_api::{
+        input::{GestureRecognizer, Gestures, InputRecorder, RawInput},
+        renderer::{Cursor, Drawable, Event, Key, KeyEvent, KeyState, Modifiers, MouseButton, Renderer, Result},
+        Bounds, Point
+    };
     use essay_graphics_test::TestRenderer;
 
     use crate::layout::PosView;
 
-    use super::Layout;
+    use super::{Layout, View};
 
     #[test]
     fn layout_basic() {
@@ -410,4 +728,401 @@ mod test {
 
         assert_eq!(view.read(|v| v.pos()), Bounds::from(((270., 1800.), [90., 1800.])));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn layout_hover() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let left = layout.view(((0., 0.), [1., 1.]), EventView::new());
+        let right = layout.view(((1., 0.), [1., 1.]), EventView::new());
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+        assert_eq!(renderer.cursor(), Cursor::Default);
+
+        layout.event(&mut renderer, &Event::MouseMove(Point(50., 50.)));
+        assert_eq!(renderer.cursor(), Cursor::Pointer);
+
+        layout.event(&mut renderer, &Event::MouseMove(Point(60., 50.)));
+        layout.event(&mut renderer, &Event::MouseMove(Point(150., 50.)));
+        layout.event(&mut renderer, &Event::Leave(Point(250., 50.)));
+
+        assert_eq!(left.read(|v| v.events.clone()), vec![
+            "Enter(Point(50.0, 50.0))",
+            "MouseMove(Point(50.0, 50.0))",
+            "MouseMove(Point(60.0, 50.0))",
+            "Leave(Point(150.0, 50.0))",
+        ]);
+
+        assert_eq!(right.read(|v| v.events.clone()), vec![
+            "Enter(Point(150.0, 50.0))",
+            "MouseMove(Point(150.0, 50.0))",
+            "Leave(Point(250.0, 50.0))",
+        ]);
+    }
+
+    #[test]
+    fn layout_propagation() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let back = layout.view(((0., 0.), [2., 1.]), EventView::new());
+        let front = layout.view(((0., 0.), [1., 1.]), EventView::handled());
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+
+        // front handles the press, so back never sees it
+        assert!(layout.event(&mut renderer, &Event::MouseLeftPress(Point(50., 50.), Modifiers::NONE)));
+
+        // front captured the pointer, so the drag and release outside its bounds go to it
+        layout.event(&mut renderer, &Event::MouseLeftDrag(
+            Point(50., 50.), Point(150., 50.), Modifiers::NONE
+        ));
+        layout.event(&mut renderer, &Event::MouseLeftRelease(Point(150., 50.), Modifiers::NONE));
+
+        // outside front, so back sees the press, but doesn't handle it
+        assert!(! layout.event(&mut renderer, &Event::MouseLeftPress(Point(150., 50.), Modifiers::NONE)));
+        layout.event(&mut renderer, &Event::MouseLeftRelease(Point(150., 50.), Modifiers::NONE));
+
+        // a right-drag zoom from front into back in the recognizer's order,
+        // where the zoom precedes the release that ends the capture
+        let mut recognizer = GestureRecognizer::new(Gestures::new());
+
+        for (time, input) in [
+            (0, RawInput::CursorMoved(Point(50., 50.))),
+            (10, RawInput::ButtonPress(MouseButton::Right)),
+            (20, RawInput::CursorMoved(Point(150., 50.))),
+            (30, RawInput::ButtonRelease(MouseButton::Right)),
+        ] {
+            for event in recognizer.input(Duration::from_millis(time), &input) {
+                layout.event(&mut renderer, &event);
+            }
+        }
+
+        // the captured moves go only to front, which leaves once the
+        // release ends the capture
+        assert_eq!(front.read(|v| v.events.clone()), vec![
+            "FocusGained",
+            "MouseLeftPress(Point(50.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseLeftDrag(Point(50.0, 50.0), Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseLeftRelease(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "FocusLost",
+            "Enter(Point(50.0, 50.0))",
+            "MouseMove(Point(50.0, 50.0))",
+            "FocusGained",
+            "MouseRightPress(Point(50.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseMove(Point(150.0, 50.0))",
+            "MouseRightDrag(Point(50.0, 50.0), Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "ZoomBounds(Point(50.0, 50.0), Point(150.0, 50.0))",
+            "MouseRightRelease(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "Leave(Point(150.0, 50.0))",
+        ]);
+
+        assert_eq!(back.read(|v| v.events.clone()), vec![
+            "Enter(Point(150.0, 50.0))",
+            "FocusGained",
+            "MouseLeftPress(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseLeftRelease(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "FocusLost",
+        ]);
+    }
+
+    #[test]
+    fn layout_chord() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let back = layout.view(((0., 0.), [2., 1.]), EventView::new());
+        let front = layout.view(((0., 0.), [1., 1.]), EventView::handled());
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+
+        layout.event(&mut renderer, &Event::MouseMove(Point(50., 50.)));
+        layout.event(&mut renderer, &Event::MouseLeftPress(Point(50., 50.), Modifiers::NONE));
+        layout.event(&mut renderer, &Event::MouseMove(Point(150., 50.)));
+
+        // front keeps the capture until both buttons are released
+        layout.event(&mut renderer, &Event::MouseRightPress(Point(150., 50.), Modifiers::NONE));
+        layout.event(&mut renderer, &Event::MouseLeftRelease(Point(150., 50.), Modifiers::NONE));
+        layout.event(&mut renderer, &Event::MouseMove(Point(160., 50.)));
+        layout.event(&mut renderer, &Event::MouseRightRelease(Point(160., 50.), Modifiers::NONE));
+
+        assert_eq!(front.read(|v| v.events.clone()), vec![
+            "Enter(Point(50.0, 50.0))",
+            "MouseMove(Point(50.0, 50.0))",
+            "FocusGained",
+            "MouseLeftPress(Point(50.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseMove(Point(150.0, 50.0))",
+            "MouseRightPress(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseLeftRelease(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "MouseMove(Point(160.0, 50.0))",
+            "MouseRightRelease(Point(160.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "Leave(Point(160.0, 50.0))",
+        ]);
+
+        assert_eq!(back.read(|v| v.events.clone()), vec![
+            "Enter(Point(50.0, 50.0))",
+        ]);
+    }
+
+    #[test]
+    fn layout_focus() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let left = layout.view(((0., 0.), [1., 1.]), EventView::new());
+        let right = layout.view(((1., 0.), [1., 1.]), EventView::new());
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+
+        let tab = |modifiers| Event::Key(
+            Point(0., 0.),
+            KeyEvent::new(Key::Tab, KeyState::Press, modifiers)
+        );
+
+        // unfocused keys go nowhere
+        assert!(! layout.event(&mut renderer, &Event::KeyPress(Point(50., 50.), 'a')));
+
+        layout.event(&mut renderer, &Event::MouseLeftPress(Point(150., 50.), Modifiers::NONE));
+        layout.event(&mut renderer, &Event::KeyPress(Point(50., 50.), 'b'));
+        assert!(right.is_focus());
+
+        layout.event(&mut renderer, &tab(Modifiers::NONE));
+        layout.event(&mut renderer, &Event::KeyPress(Point(150., 50.), 'c'));
+        assert!(left.is_focus());
+
+        layout.event(&mut renderer, &tab(Modifiers { shift: true, ..Modifiers::NONE }));
+        assert!(right.is_focus());
+
+        left.focus();
+        layout.event(&mut renderer, &Event::KeyPress(Point(150., 50.), 'd'));
+
+        // the focused view declines Tab before it moves focus
+        let tab_text = format!("{:?}", tab(Modifiers::NONE));
+        let shift_tab_text = format!("{:?}", tab(Modifiers { shift: true, ..Modifiers::NONE }));
+
+        assert_eq!(left.read(|v| v.events.clone()), vec![
+            "FocusGained",
+            "KeyPress(Point(150.0, 50.0), 'c')",
+            shift_tab_text.as_str(),
+            "FocusLost",
+            "FocusGained",
+            "KeyPress(Point(150.0, 50.0), 'd')",
+        ]);
+
+        assert_eq!(right.read(|v| v.events.clone()), vec![
+            "FocusGained",
+            "MouseLeftPress(Point(150.0, 50.0), Modifiers { shift: false, ctrl: false, alt: false, super_key: false })",
+            "KeyPress(Point(50.0, 50.0), 'b')",
+            tab_text.as_str(),
+            "FocusLost",
+            "FocusGained",
+            "FocusLost",
+        ]);
+    }
+
+    #[test]
+    fn layout_focus_tab() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let text = layout.view(((0., 0.), [1., 1.]), EventView::handled());
+        let other = layout.view(((1., 0.), [1., 1.]), EventView::new());
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+
+        // a view that handles Tab keeps focus and gets the Tab text
+        let mut recognizer = GestureRecognizer::new(Gestures::new());
+
+        for (time, input) in [
+            (0, RawInput::CursorMoved(Point(50., 50.))),
+            (10, RawInput::ButtonPress(MouseButton::Left)),
+            (20, RawInput::ButtonRelease(MouseButton::Left)),
+            (30, RawInput::Key(Key::Tab, KeyState::Press)),
+            (40, RawInput::Key(Key::Tab, KeyState::Release)),
+        ] {
+            for event in recognizer.input(Duration::from_millis(time), &input) {
+                layout.event(&mut renderer, &event);
+            }
+        }
+
+        assert!(text.is_focus());
+
+        let events: Vec<String> = text.read(|v| v.events.iter()
+            .map(|e| e[..e.find('(').unwrap_or(e.len())].to_string())
+            .collect()
+        );
+
+        assert_eq!(events, vec![
+            "Enter",
+            "MouseMove",
+            "FocusGained",
+            "MouseLeftPress",
+            "MouseLeftRelease",
+            "Key",
+            "KeyPress",
+            "Key",
+        ]);
+
+        // a view that doesn't handle Tab loses focus, and the Tab text
+        // isn't sent to the next view
+        other.focus();
+
+        for (time, input) in [
+            (1000, RawInput::Key(Key::Tab, KeyState::Press)),
+            (1010, RawInput::Key(Key::Tab, KeyState::Release)),
+        ] {
+            for event in recognizer.input(Duration::from_millis(time), &input) {
+                layout.event(&mut renderer, &event);
+            }
+        }
+
+        assert!(text.is_focus());
+        assert!(! other.read(|v| v.events.iter().any(|e| e.starts_with("KeyPress"))));
+    }
+
+    #[test]
+    fn layout_focus_request() {
+        let mut layout = Layout::new();
+
+        let bounds = Bounds::from([200., 100.]);
+        let mut renderer = TestRenderer::new(&bounds);
+
+        let left = layout.view(((0., 0.), [1., 1.]), EventView::new());
+        let right = layout.view(((1., 0.), [1., 1.]), FocusView(left.clone()));
+
+        layout.event(&mut renderer, &Event::Resize(bounds));
+
+        // right takes focus with the press, then passes it to left before
+        // the press returns
+        layout.event(&mut renderer, &Event::MouseLeftPress(Point(150., 50.), Modifiers::NONE));
+        assert!(left.is_focus());
+
+        right.focus();
+        layout.draw(&mut renderer).unwrap();
+        assert!(right.is_focus());
+
+        assert_eq!(left.read(|v| v.events.clone()), vec![
+            "FocusGained",
+            "FocusLost",
+        ]);
+    }
+
+    #[test]
+    fn layout_replay() {
+        let mut layout = Layout::new();
+
+        let mut renderer = TestRenderer::new([200., 100.]);
+
+        let pos = layout.view(((0., 0.), [1., 1.]), PosView::new());
+        let view = layout.view(((0., 0.), [1., 1.]), EventView::new());
+
+        // a pan, then a double click after the first click's timeout
+        let recording: InputRecorder = "
+            0 resize 200 100
+            10 move 50 50
+            20 press left
+            30 move 80 50
+            40 release left
+            1000 press left
+            1050 release left
+            1200 press left
+            1250 release left
+        ".parse().unwrap();
+
+        recording.replay(&Gestures::new(), &mut layout, &mut renderer);
+
+        assert_eq!(pos.read(|v| v.pos()), Bounds::from([200., 100.]));
+
+        let events: Vec<String> = view.read(|v| v.events.iter()
+            .map(|e| e[..e.find('(').unwrap_or(e.len())].to_string())
+            .collect()
+        );
+
+        assert_eq!(events, vec![
+            "Enter",
+            "MouseMove",
+            "FocusGained",
+            "MouseLeftPress",
+            "MouseMove",
+            "MouseLeftDrag",
+            "Pan",
+            "MouseLeftRelease",
+            "MouseLeftPress",
+            "MouseLeftRelease",
+            "MouseLeftPress",
+            "ResetView",
+            "MouseLeftRelease",
+        ]);
+    }
+
+    struct EventView {
+        events: Vec<String>,
+        is_handled: bool,
+    }
+
+    impl EventView {
+        fn new() -> Self {
+            Self {
+                events: Vec::new(),
+                is_handled: false,
+            }
+        }
+
+        fn handled() -> Self {
+            Self {
+                events: Vec::new(),
+                is_handled: true,
+            }
+        }
+    }
+
+    // focuses another view when pressed
+    struct FocusView(View<EventView>);
+
+    impl Drawable for FocusView {
+        fn draw(&mut self, _renderer: &mut dyn Renderer) -> Result<()> {
+            Ok(())
+        }
+
+        fn event(&mut self, _renderer: &mut dyn Renderer, event: &Event) -> bool {
+            if let Event::MouseLeftPress(..) = event {
+                self.0.focus();
+
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    impl Drawable for EventView {
+        fn draw(&mut self, _renderer: &mut dyn Renderer) -> Result<()> {
+            Ok(())
+        }
+
+        fn event(&mut self, renderer: &mut dyn Renderer, event: &Event) -> bool {
+            if let Event::MouseMove(_) = event {
+                renderer.set_cursor(Cursor::Pointer);
+            }
+
+            if let Event::Resize(_) = event {
+                false
+            } else {
+                self.events.push(format!("{:?}", event));
+
+                self.is_handled
+            }
+        }
+    }
+}